@@ -0,0 +1,28 @@
+use musicbx::std::{osc, util};
+use musicbx::{node, FromSampleRate};
+
+#[node(
+    freq -> osc.freq,
+    feedback -> feedback_amp.a,
+    osc.output -> delay(sample) feedback_amp.b,
+    feedback_amp.output -> osc.phase_mod,
+    osc.output -> out,
+)]
+#[derive(FromSampleRate)]
+pub struct FeedbackFm {
+    osc: osc::SinOsc,
+    feedback_amp: util::Mul,
+}
+
+#[node(
+    freq -> osc.freq,
+    feedback -> feedback_amp.a,
+    osc.output -> delay(block) feedback_amp.b,
+    feedback_amp.output -> osc.phase_mod,
+    osc.output -> out,
+)]
+#[derive(FromSampleRate)]
+pub struct BlockFeedbackFm {
+    osc: osc::SinOsc,
+    feedback_amp: util::Mul,
+}
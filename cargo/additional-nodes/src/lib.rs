@@ -1,4 +1,32 @@
+use musicbx::types::{ModuleDefinition, NodeDefinition};
+
 pub mod fast_tremolo;
+pub mod feedback;
+pub mod ring_mod;
+pub mod ring_tremolo;
 pub mod synth;
 pub mod test;
 pub mod test_fm;
+
+#[derive(Clone, Copy)]
+pub struct AdditionalModuleDefinition;
+
+impl ModuleDefinition for AdditionalModuleDefinition {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        NODE_DEFINITIONS.definitions()
+    }
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        NODE_DEFINITIONS.info_for_uid(uid)
+    }
+}
+
+static NODE_DEFINITIONS: &[NodeDefinition] = &[
+    fast_tremolo::FastTremolo::definition(),
+    feedback::BlockFeedbackFm::definition(),
+    feedback::FeedbackFm::definition(),
+    ring_mod::RingMod::definition(),
+    ring_tremolo::RingTremolo::definition(),
+    synth::Synth::definition(),
+    test_fm::TestFm::definition(),
+];
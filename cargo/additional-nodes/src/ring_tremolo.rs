@@ -0,0 +1,5 @@
+// сгенерированный узел ссылается на подпатчи по их именам
+use crate::fast_tremolo::FastTremolo;
+use crate::ring_mod::RingMod;
+
+include!(concat!(env!("OUT_DIR"), "/RingTremolo.rs"));
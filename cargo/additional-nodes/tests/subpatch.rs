@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use additional_nodes::fast_tremolo::FastTremolo;
+use additional_nodes::ring_mod::RingMod;
+use additional_nodes::ring_tremolo::RingTremolo;
+use additional_nodes::test_fm::TestFm;
+use additional_nodes::AdditionalModuleDefinition;
+use musicbx::dynamic::PatchModule;
+use musicbx::std::StdModuleDefinition;
+use musicbx::types::patch::Patch;
+use musicbx::types::{validate, ModuleDefinition, NodeDefinition};
+
+fn patches() -> BTreeMap<String, Patch> {
+    let mut patches = BTreeMap::new();
+    for entry in fs::read_dir("nodes").unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_stem().unwrap().to_str().unwrap().to_string();
+        let patch = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        patches.insert(name, patch);
+    }
+    patches
+}
+
+fn socket_names(definition: &NodeDefinition) -> [Vec<&str>; 3] {
+    let sorted = |mut names: Vec<&'static str>| {
+        names.sort_unstable();
+        names
+    };
+    [
+        sorted(definition.inputs.iter().map(|x| x.name).collect()),
+        sorted(definition.outputs.iter().map(|x| x.name).collect()),
+        sorted(definition.parameters.iter().map(|x| x.name).collect()),
+    ]
+}
+
+#[test]
+fn generated_and_runtime_subpatches_have_the_same_uids() {
+    let module = PatchModule::new(&StdModuleDefinition, patches()).unwrap();
+
+    let generated = [
+        FastTremolo::definition(),
+        RingMod::definition(),
+        RingTremolo::definition(),
+        TestFm::definition(),
+    ];
+    for definition in &generated {
+        let runtime = module
+            .info_for_uid(definition.uid)
+            .unwrap_or_else(|| panic!("{} is not a runtime subpatch", definition.uid));
+        assert_eq!(socket_names(runtime), socket_names(definition));
+    }
+}
+
+#[test]
+fn patches_can_use_generated_nodes_by_the_same_uids() {
+    // узлы подпатчей берутся из скомпилированного крейта, а не из файлов
+    let module: [&dyn ModuleDefinition; 2] = [&AdditionalModuleDefinition, &StdModuleDefinition];
+    let patch = &patches()["RingTremolo"];
+    assert_eq!(validate(patch, &module.as_slice()), vec![]);
+}
@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex};
+use std::sync::Mutex;
 
 use anyhow::anyhow;
 use cpal::{
@@ -6,22 +6,28 @@ use cpal::{
     Device, OutputCallbackInfo, Sample, SampleFormat, SampleRate, Stream, StreamConfig,
     StreamError, SupportedStreamConfig,
 };
-use glicol_synth::{AudioContext, AudioContextBuilder};
 
-use crate::model::configuration::{DeviceConfiguration, SampleRateConfiguration};
-use crate::nodes::NodeWrapper;
+use musicbx::dynamic::{BlockStream, GraphController, GraphProcessor};
+use musicbx::types::patch::{Cable, SYNTHETIC_OUTPUT_UID};
+use musicbx::{AudioBuffer, MAX_BLOCK_SIZE};
+
+use crate::model::configuration::{
+    ChannelsConfiguration, DeviceConfiguration, SampleRateConfiguration,
+};
+use crate::nodes::NodeFactory;
+
+pub const DEFAULT_BLOCK_SIZE: usize = 128;
+
+// сколько изменений графа может ждать следующего блока
+const COMMAND_QUEUE_CAPACITY: usize = 1024;
 
 pub trait AppDelegate {
-    fn add_node(&self, node: &mut dyn NodeWrapper);
-    fn connect_nodes(
-        &self,
-        from: &dyn NodeWrapper,
-        from_output: &str,
-        to: &dyn NodeWrapper,
-        to_input: &str,
-    );
-    fn set_parameter(&self, node: &dyn NodeWrapper, param_idx: u8, param_value: f32);
-    fn reset(&self);
+    fn add_node(&self, id: usize, factory: &dyn NodeFactory) -> anyhow::Result<()>;
+    fn remove_node(&self, id: usize) -> anyhow::Result<()>;
+    fn connect_nodes(&self, cable: &Cable) -> anyhow::Result<()>;
+    fn disconnect_nodes(&self, cable: &Cable) -> anyhow::Result<()>;
+    fn set_parameter(&self, id: usize, name: &str, value: f32) -> anyhow::Result<()>;
+    fn reset(&self) -> anyhow::Result<()>;
     fn output_configuration(&self) -> DeviceConfiguration;
 }
 
@@ -35,24 +41,41 @@ impl Default for Box<dyn AppDelegate> {
 pub struct NoopAppDelegate;
 
 impl AppDelegate for NoopAppDelegate {
-    fn add_node(&self, _: &mut dyn NodeWrapper) {}
-    fn connect_nodes(&self, _: &dyn NodeWrapper, _: &str, _: &dyn NodeWrapper, _: &str) {}
-    fn set_parameter(&self, _: &dyn NodeWrapper, _: u8, _: f32) {}
-    fn reset(&self) {}
+    fn add_node(&self, _: usize, _: &dyn NodeFactory) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn remove_node(&self, _: usize) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn connect_nodes(&self, _: &Cable) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn disconnect_nodes(&self, _: &Cable) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn set_parameter(&self, _: usize, _: &str, _: f32) -> anyhow::Result<()> {
+        Ok(())
+    }
+    fn reset(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
     fn output_configuration(&self) -> DeviceConfiguration {
         DeviceConfiguration {
             current: None,
             available: vec![],
             sample_rate: None,
+            channels: None,
         }
     }
 }
 
 pub struct CpalAppDelegate {
-    context: Arc<Mutex<AudioContext<1>>>,
+    controller: Mutex<GraphController>,
     _audio_stream: Mutex<Option<Stream>>,
     output_name: String,
     sample_rate: u32,
+    channels: u16,
+    available_channels: Vec<u16>,
 }
 
 impl CpalAppDelegate {
@@ -64,6 +87,7 @@ impl CpalAppDelegate {
 pub struct CpalAppDelegateBuilder {
     output_name: Option<String>,
     sr: Option<SampleRate>,
+    block_size: Option<usize>,
     err_fn: Box<dyn FnMut(StreamError) + Send + 'static>,
 }
 
@@ -72,6 +96,7 @@ impl Default for CpalAppDelegateBuilder {
         Self {
             output_name: None,
             sr: None,
+            block_size: None,
             err_fn: Box::new(|_| {}),
         }
     }
@@ -88,11 +113,15 @@ impl CpalAppDelegateBuilder {
     #[allow(dead_code)]
     pub fn sr(self, sr: u32) -> Self {
         Self {
-            sr: Some(SampleRate(sr as u32)),
+            sr: Some(SampleRate(sr)),
             ..self
         }
     }
 
+    pub fn block_size(self, block_size: Option<usize>) -> Self {
+        Self { block_size, ..self }
+    }
+
     pub fn on_error(self, callback: impl FnMut(StreamError) + Send + 'static) -> Self {
         Self {
             err_fn: Box::new(callback),
@@ -104,9 +133,17 @@ impl CpalAppDelegateBuilder {
         let Self {
             output_name,
             sr,
+            block_size,
             err_fn,
         } = self;
 
+        let block_size = block_size.unwrap_or(DEFAULT_BLOCK_SIZE);
+        if !(1..=MAX_BLOCK_SIZE).contains(&block_size) {
+            Err(anyhow!(
+                "Block size must be in range 1..={MAX_BLOCK_SIZE}, got {block_size}"
+            ))?;
+        }
+
         let host = cpal::default_host();
         let device = host
             .output_devices()
@@ -115,30 +152,32 @@ impl CpalAppDelegateBuilder {
             .unwrap_or_else(|| host.default_output_device().unwrap());
         let config = get_output_config(&device, sr)?;
         let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+        let available_channels = get_available_channels(&device)?;
 
-        let context: AudioContext<1> = AudioContextBuilder::new()
-            .sr(config.sample_rate().0 as usize)
-            .build();
-        let context = Arc::new(Mutex::new(context));
+        let (controller, processor) =
+            GraphController::new(sample_rate as f32, COMMAND_QUEUE_CAPACITY);
 
         let stream = match config.sample_format() {
             SampleFormat::F32 => {
-                start_audio_stream::<f32, 1>(context.clone(), &device, &config.into(), err_fn)
+                start_audio_stream::<f32>(processor, block_size, &device, &config.into(), err_fn)
             }
             SampleFormat::I16 => {
-                start_audio_stream::<i16, 1>(context.clone(), &device, &config.into(), err_fn)
+                start_audio_stream::<i16>(processor, block_size, &device, &config.into(), err_fn)
             }
             SampleFormat::U16 => {
-                start_audio_stream::<u16, 1>(context.clone(), &device, &config.into(), err_fn)
+                start_audio_stream::<u16>(processor, block_size, &device, &config.into(), err_fn)
             }
         }?;
         stream.play()?;
 
         Ok(CpalAppDelegate {
-            context,
+            controller: Mutex::new(controller),
             _audio_stream: Mutex::new(Some(stream)),
             output_name: device.name()?,
             sample_rate,
+            channels,
+            available_channels,
         })
     }
 }
@@ -172,8 +211,20 @@ fn get_output_config(
     Ok(config)
 }
 
-fn start_audio_stream<T: Sample, const N: usize>(
-    context: Arc<Mutex<AudioContext<N>>>,
+fn get_available_channels(device: &Device) -> anyhow::Result<Vec<u16>> {
+    let mut channels: Vec<u16> = device
+        .supported_output_configs()?
+        .map(|cfg| cfg.channels())
+        .collect();
+    channels.sort_unstable();
+    channels.dedup();
+
+    Ok(channels)
+}
+
+fn start_audio_stream<T: Sample>(
+    mut processor: GraphProcessor,
+    block_size: usize,
     device: &Device,
     config: &StreamConfig,
     mut err_fn: Box<dyn FnMut(StreamError) + Send + 'static>,
@@ -185,19 +236,14 @@ fn start_audio_stream<T: Sample, const N: usize>(
         err_fn(err);
     };
 
+    // изменения графа processor забирает из очереди без блокировок
+    let mut blocks = BlockStream::new(channels, block_size);
+
     let stream = device.build_output_stream(
         config,
         move |data: &mut [T], _: &OutputCallbackInfo| {
-            let context = &mut context.lock().unwrap();
-
-            for (_sample_idx, frame) in data.chunks_mut(channels).enumerate() {
-                let block = context.next_block();
-
-                for (channel_idx, sample) in frame.iter_mut().enumerate() {
-                    let s = &block[channel_idx][0];
-                    *sample = Sample::from::<f32>(s);
-                }
-            }
+            let process = |n, block: &mut AudioBuffer| processor.process(n, block);
+            blocks.fill(data, process, |x| Sample::from::<f32>(&x));
         },
         err_fn,
     )?;
@@ -206,32 +252,49 @@ fn start_audio_stream<T: Sample, const N: usize>(
 }
 
 impl AppDelegate for CpalAppDelegate {
-    fn add_node(&self, node: &mut dyn NodeWrapper) {
-        let context = &mut self.context.lock().unwrap();
-        node.add_to_context(context);
+    fn add_node(&self, id: usize, factory: &dyn NodeFactory) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+
+        if factory.uid() == SYNTHETIC_OUTPUT_UID {
+            controller.add_output(id)?;
+        } else {
+            let node = factory
+                .create_instance(self.sample_rate as f32)
+                .ok_or_else(|| anyhow!("Can't create node with uid {}", factory.uid()))?;
+            controller.add_node_instance(id, factory.info().definition.clone(), node)?;
+        }
+
+        Ok(())
+    }
+
+    fn remove_node(&self, id: usize) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+        controller.remove_node(id)?;
+        Ok(())
     }
 
-    fn connect_nodes(
-        &self,
-        from: &dyn NodeWrapper,
-        from_output: &str,
-        to: &dyn NodeWrapper,
-        to_input: &str,
-    ) {
-        let from = from.output(from_output).unwrap();
-        let (order, to) = to.input(to_input).unwrap();
-        let context = &mut self.context.lock().unwrap();
-        context.connect_with_order(from, to, order);
+    fn connect_nodes(&self, cable: &Cable) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+        controller.connect(cable)?;
+        Ok(())
     }
 
-    fn set_parameter(&self, node: &dyn NodeWrapper, param_idx: u8, param_value: f32) {
-        let context = &mut self.context.lock().unwrap();
-        node.set_parameter(context, param_idx, param_value);
+    fn disconnect_nodes(&self, cable: &Cable) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+        controller.disconnect(cable)?;
+        Ok(())
     }
 
-    fn reset(&self) {
-        let context = &mut self.context.lock().unwrap();
-        context.reset();
+    fn set_parameter(&self, id: usize, name: &str, value: f32) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+        controller.set_parameter(id, name, value)?;
+        Ok(())
+    }
+
+    fn reset(&self) -> anyhow::Result<()> {
+        let controller = &mut self.controller.lock().unwrap();
+        controller.reset()?;
+        Ok(())
     }
 
     fn output_configuration(&self) -> DeviceConfiguration {
@@ -242,6 +305,10 @@ impl AppDelegate for CpalAppDelegate {
                 current: self.sample_rate,
                 available: vec![],
             }),
+            channels: Some(ChannelsConfiguration {
+                current: self.channels,
+                available: self.available_channels.clone(),
+            }),
         }
     }
 }
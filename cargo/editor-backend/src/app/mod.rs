@@ -1,6 +1,8 @@
+use std::path::PathBuf;
 use std::sync::mpsc::Sender;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
+use std::time::Duration;
 
 use lazy_static::lazy_static;
 
@@ -58,6 +60,8 @@ impl App {
 // при этом мы обещаем, что с этим указателем будет работать только один поток
 unsafe impl Send for AppState {}
 
+pub type AvailableNodesListener = Arc<dyn Fn(&[&NodeInfo])>;
+
 #[derive(Clone)]
 pub enum AppMsg {
     Reset,
@@ -66,10 +70,12 @@ pub enum AppMsg {
     AddCable(Cable),
     RemoveCable(Cable),
     ChangeCurrentOutput { output: Option<String> },
-    RegisterAvailableNodesListener(Arc<dyn Fn(&[&NodeInfo])>),
+    ChangeBlockSize { block_size: usize },
+    RegisterAvailableNodesListener(AvailableNodesListener),
     RegisterConfigurationListener(Arc<dyn Fn(&IOConfiguration)>),
     SetParameter { id: usize, index: u8, value: f32 },
     RefreshConfiguration,
+    RenderToWav { path: PathBuf, duration: Duration },
 }
 
 unsafe impl Send for AppMsg {}
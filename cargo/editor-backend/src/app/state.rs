@@ -1,28 +1,40 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
+use anyhow::anyhow;
 use cpal::traits::{DeviceTrait, HostTrait};
 
-use musicbx::types::patch::Cable;
+use additional_nodes::test_fm::TestFm;
+use musicbx::dynamic::render_patch_to_wav;
+use musicbx::std::StdModuleDefinition;
+use musicbx::types::patch::{Cable, GridOffset, Node, Patch};
+use musicbx::types::{check_cable_signal, validate, ModuleDefinition};
 
 use crate::app::delegate::{AppDelegate, CpalAppDelegate};
+use crate::app::AvailableNodesListener;
 use crate::model::configuration::IOConfiguration;
+use crate::nodes::descriptions;
 use crate::nodes::{
-    AddNodeDescription, AmpNodeDescription, HardClipNodeDescription, MulNodeDescription,
-    NodeFactory, NodeInfo, NodeWrapper, NoiseNodeDescription, OutputNodeDescription,
-    SimpleSawNodeDescription, SinNodeDescription, TestFmNodeDescription,
+    DynNodeFactory, ModuleNodeFactory, NodeFactories, NodeFactory, OutputNodeDescription,
 };
 use crate::util::Observable;
 use crate::{App, AppMsg};
 
+const DEFAULT_SAMPLE_RATE: u32 = 48000;
+const DEFAULT_CHANNELS: u16 = 2;
+
 #[derive(Default)]
 pub struct AppState {
     delegate: Box<dyn AppDelegate>,
     available_nodes: Observable<Vec<Box<dyn NodeFactory>>>,
     configuration: Observable<IOConfiguration>,
-    nodes: HashMap<usize, Box<dyn NodeWrapper>>,
+    // uid узлов по их id
+    nodes: HashMap<usize, String>,
     cables: Vec<Cable>,
     parameters: HashMap<(usize, u8), f32>,
+    block_size: Option<usize>,
 }
 
 impl AppState {
@@ -35,64 +47,78 @@ impl AppState {
 
     pub fn update(&mut self, msg: &AppMsg) -> anyhow::Result<()> {
         match msg {
-            AppMsg::Reset => self.reset(),
-            AppMsg::AddNode { uid, id } => self.add_node(uid, *id),
-            AppMsg::RemoveNode { id } => self.remove_node(*id),
-            AppMsg::AddCable(cable) => self.add_cable(cable),
-            AppMsg::RemoveCable(cable) => self.remove_cable(cable),
+            AppMsg::Reset => self.reset()?,
+            AppMsg::AddNode { uid, id } => self.add_node(uid, *id)?,
+            AppMsg::RemoveNode { id } => self.remove_node(*id)?,
+            AppMsg::AddCable(cable) => self.add_cable(cable)?,
+            AppMsg::RemoveCable(cable) => self.remove_cable(cable)?,
             AppMsg::ChangeCurrentOutput { output } => {
                 let output = output.as_ref().map(|s| s.as_str());
                 self.change_current_output(output)?
             }
+            AppMsg::ChangeBlockSize { block_size } => self.change_block_size(*block_size)?,
             AppMsg::RegisterAvailableNodesListener(listener) => {
                 self.add_available_nodes_listener(listener.clone())
             }
             AppMsg::RegisterConfigurationListener(listener) => {
                 self.add_configuration_listener(listener.clone())
             }
-            AppMsg::SetParameter { id, index, value } => self.set_parameter(*id, *index, *value),
+            AppMsg::SetParameter { id, index, value } => self.set_parameter(*id, *index, *value)?,
             AppMsg::RefreshConfiguration => self.refresh_configuration()?,
+            AppMsg::RenderToWav { path, duration } => self.render_to_wav(path, *duration)?,
         };
         Ok(())
     }
 
-    fn reset(&mut self) {
+    fn reset(&mut self) -> anyhow::Result<()> {
         self.nodes.clear();
         self.cables.clear();
         self.parameters.clear();
-        self.recreate_context();
+        self.recreate_context()
     }
 
-    fn add_node(&mut self, uid: &str, id: usize) {
-        let node_desc = self.available_nodes.data.iter().find(|x| x.uid() == uid);
-
-        if let Some(node_desc) = node_desc {
-            let mut node = node_desc.create_instance(id);
-            self.delegate.add_node(node.as_mut());
-            self.nodes.insert(id, node);
-        } else {
-            eprintln!("Node description with uid {uid} not found");
-        }
+    fn add_node(&mut self, uid: &str, id: usize) -> anyhow::Result<()> {
+        self.delegate.add_node(id, self.factory(uid)?)?;
+        self.nodes.insert(id, uid.to_string());
+        Ok(())
     }
 
-    fn remove_node(&mut self, id: usize) {
+    fn remove_node(&mut self, id: usize) -> anyhow::Result<()> {
+        self.delegate.remove_node(id)?;
         self.nodes.remove(&id);
         self.cables
             .retain(|x| x.from.node_id != id && x.to.node_id != id);
-        self.recreate_context();
+        self.parameters.retain(|(node_id, _), _| *node_id != id);
+        Ok(())
     }
 
-    fn add_cable(&mut self, cable: &Cable) {
+    fn add_cable(&mut self, cable: &Cable) -> anyhow::Result<()> {
+        self.delegate.connect_nodes(cable)?;
         self.cables.push(cable.clone());
-        self.do_connect_nodes(cable);
+
+        // кабель всё равно подключается, высота тона и частота преобразуются в графе
+        let definition = |id| Some(&self.factory(self.nodes.get(&id)?).ok()?.info().definition);
+        if let (Some(from), Some(to)) =
+            (definition(cable.from.node_id), definition(cable.to.node_id))
+        {
+            if let Some(warning) = check_cable_signal(cable, from, to) {
+                eprintln!("Got warning {warning}");
+            }
+        }
+        Ok(())
     }
 
-    fn remove_cable(&mut self, cable: &Cable) {
-        self.cables.retain(|x| x != cable);
-        self.recreate_context();
+    fn remove_cable(&mut self, cable: &Cable) -> anyhow::Result<()> {
+        if self.cables.contains(cable) {
+            self.delegate.disconnect_nodes(cable)?;
+            self.cables.retain(|x| x != cable);
+        }
+        Ok(())
     }
 
-    pub fn add_available_nodes_listener(&mut self, listener: Arc<dyn Fn(&[&NodeInfo])>) {
+    // слушатели вызываются только из потока состояния, см. unsafe impl Send for AppState
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub fn add_available_nodes_listener(&mut self, listener: AvailableNodesListener) {
         let listener = move |node_descriptions: &Vec<Box<dyn NodeFactory + 'static>>| {
             let node_infos: Vec<_> = node_descriptions.iter().map(|x| x.info()).collect();
             listener(&node_infos);
@@ -106,44 +132,128 @@ impl AppState {
         self.configuration.add_listener(listener);
     }
 
-    fn recreate_context(&mut self) {
-        self.delegate.reset();
+    fn recreate_context(&mut self) -> anyhow::Result<()> {
+        self.delegate.reset()?;
 
-        for node in self.nodes.values_mut() {
-            self.delegate.add_node(node.as_mut());
+        for (id, uid) in &self.nodes {
+            self.delegate.add_node(*id, self.factory(uid)?)?;
         }
 
         for cable in &self.cables {
-            self.do_connect_nodes(cable);
+            self.delegate.connect_nodes(cable)?;
         }
 
         for ((id, index), value) in &self.parameters {
-            let node = self.nodes.get(id);
-            if let Some(node) = node {
-                self.delegate.set_parameter(node.as_ref(), *index, *value);
-            }
+            self.do_set_parameter(*id, *index, *value)?;
         }
-    }
 
-    fn do_connect_nodes(&self, cable: &Cable) {
-        let from = &self.nodes[&cable.from.node_id];
-        let to = &self.nodes[&cable.to.node_id];
+        Ok(())
+    }
 
-        self.delegate.connect_nodes(
-            from.as_ref(),
-            &cable.from.socket_name,
-            to.as_ref(),
-            &cable.to.socket_name,
-        );
+    fn factory(&self, uid: &str) -> anyhow::Result<&dyn NodeFactory> {
+        self.available_nodes
+            .data
+            .iter()
+            .find(|x| x.uid() == uid)
+            .map(|x| x.as_ref())
+            .ok_or_else(|| anyhow!("Node description with uid {uid} not found"))
     }
 
-    fn set_parameter(&mut self, node_id: usize, index: u8, value: f32) {
-        let node = self.nodes.get(&node_id).unwrap();
+    fn set_parameter(&mut self, node_id: usize, index: u8, value: f32) -> anyhow::Result<()> {
+        self.do_set_parameter(node_id, index, value)?;
         self.parameters.insert((node_id, index), value);
-        self.delegate.set_parameter(node.as_ref(), index, value);
+        Ok(())
+    }
+
+    fn do_set_parameter(&self, node_id: usize, index: u8, value: f32) -> anyhow::Result<()> {
+        let uid = self
+            .nodes
+            .get(&node_id)
+            .ok_or_else(|| anyhow!("Node with id {node_id} not found"))?;
+        let parameter = self
+            .factory(uid)?
+            .info()
+            .definition
+            .parameters
+            .get(index as usize)
+            .ok_or_else(|| anyhow!("Node with uid {uid} has no parameter {index}"))?;
+
+        // из редактора приходят нормированные значения
+        let value = parameter.denormalize(value);
+        self.delegate.set_parameter(node_id, parameter.name, value)
+    }
+
+    fn render_to_wav(&self, path: &Path, duration: Duration) -> anyhow::Result<()> {
+        let configuration = self.delegate.output_configuration();
+        let sr = configuration
+            .sample_rate
+            .map_or(DEFAULT_SAMPLE_RATE, |x| x.current);
+        let channels = configuration
+            .channels
+            .map_or(DEFAULT_CHANNELS, |x| x.current);
+
+        let module = NodeFactories(&self.available_nodes.data);
+        let patch = self.patch()?;
+
+        let diagnostics = validate(&patch, &module);
+        if !diagnostics.is_empty() {
+            let diagnostics: Vec<_> = diagnostics.iter().map(ToString::to_string).collect();
+            Err(anyhow!("Invalid patch: {}", diagnostics.join("; ")))?;
+        }
+
+        render_patch_to_wav(&patch, &module, sr, channels, duration, path)?;
+
+        Ok(())
+    }
+
+    // параметры в патче хранятся нормированными, как и в редакторе
+    fn patch(&self) -> anyhow::Result<Patch> {
+        let mut nodes = Vec::new();
+
+        for (id, uid) in &self.nodes {
+            let definition = &self.factory(uid)?.info().definition;
+            let parameters = self
+                .parameters
+                .iter()
+                .filter(|((node_id, _), _)| node_id == id)
+                .filter_map(|((_, index), value)| {
+                    let parameter = definition.parameters.get(*index as usize)?;
+                    Some((parameter.name.to_string(), value.to_string()))
+                })
+                .collect();
+
+            nodes.push(Node {
+                id: *id,
+                uid: uid.clone(),
+                offset: GridOffset { x: 0, y: 0 },
+                parameters,
+                collapsed: false,
+                label: None,
+                port: None,
+                exposed: HashMap::new(),
+            });
+        }
+
+        Ok(Patch {
+            nodes,
+            cables: self.cables.clone(),
+        })
     }
 
     fn change_current_output(&mut self, output: Option<&str>) -> anyhow::Result<()> {
+        self.restart_audio(output, self.block_size)
+    }
+
+    fn change_block_size(&mut self, block_size: usize) -> anyhow::Result<()> {
+        let output = self.delegate.output_configuration().current;
+        self.restart_audio(output.as_deref(), Some(block_size))
+    }
+
+    fn restart_audio(
+        &mut self,
+        output: Option<&str>,
+        block_size: Option<usize>,
+    ) -> anyhow::Result<()> {
         let error_callback = |err| {
             eprintln!("Got stream error {err}");
             App::current().accept_message(AppMsg::RefreshConfiguration);
@@ -151,12 +261,14 @@ impl AppState {
 
         let new_context = CpalAppDelegate::builder()
             .output_name(output)
+            .block_size(block_size)
             .on_error(error_callback)
             .build()?;
 
         self.delegate = Box::new(new_context);
+        self.block_size = block_size;
         self.refresh_configuration()?;
-        self.recreate_context();
+        self.recreate_context()?;
 
         Ok(())
     }
@@ -184,15 +296,16 @@ impl AppState {
 }
 
 fn available_nodes() -> Vec<Box<dyn NodeFactory>> {
-    vec![
-        Box::new(AddNodeDescription),
-        Box::new(AmpNodeDescription),
-        Box::new(MulNodeDescription),
-        Box::new(NoiseNodeDescription),
-        Box::new(OutputNodeDescription),
-        Box::new(SimpleSawNodeDescription),
-        Box::new(SinNodeDescription),
-        Box::new(TestFmNodeDescription),
-        Box::new(HardClipNodeDescription),
-    ]
+    let mut nodes = vec![Box::new(OutputNodeDescription) as Box<dyn NodeFactory>];
+    nodes.extend(
+        StdModuleDefinition
+            .definitions()
+            .into_iter()
+            .map(|definition| ModuleNodeFactory::boxed(StdModuleDefinition, definition.clone())),
+    );
+    nodes.push(DynNodeFactory::<TestFm>::boxed(
+        TestFm::definition(),
+        descriptions::TEST_FM,
+    ));
+    nodes
 }
@@ -4,7 +4,7 @@ use jni::objects::{JClass, JObject, JValue};
 use jni::JNIEnv;
 use serde::Serialize;
 
-use musicbx::types::NodeParameterKind;
+use musicbx::types::{NodeParameterKind, ParameterCurve, SignalType};
 
 use crate::nodes::{Description, NodeInfo};
 use crate::unwrap_or_throw;
@@ -38,7 +38,7 @@ fn invoke_listener(env: JNIEnv, listener: JObject, nodes: &[Node]) -> anyhow::Re
     Ok(())
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Default)]
+#[derive(PartialEq, Debug, Serialize, Default)]
 struct Node {
     pub uid: String,
     pub name: String,
@@ -59,96 +59,103 @@ impl From<&NodeInfo> for Node {
                 .definition
                 .inputs
                 .iter()
-                .filter_map(|def| {
-                    let desc = info
-                        .description
-                        .inputs
-                        .iter()
-                        .find(|x| x.name == def.name)?;
-                    Some((def, desc).into())
-                })
+                .map(|def| (def, summary(info.description.inputs, def.name)).into())
                 .collect(),
             outputs: info
                 .definition
                 .outputs
                 .iter()
-                .filter_map(|def| {
-                    let desc = info
-                        .description
-                        .outputs
-                        .iter()
-                        .find(|x| x.name == def.name)?;
-                    Some((def, desc).into())
-                })
+                .map(|def| (def, summary(info.description.outputs, def.name)).into())
                 .collect(),
             parameters: info
                 .definition
                 .parameters
                 .iter()
-                .filter_map(|def| {
-                    let desc = info
-                        .description
-                        .parameters
-                        .iter()
-                        .find(|x| x.name == def.name)?;
-                    Some((def, desc).into())
-                })
+                .map(|def| (def, summary(info.description.parameters, def.name)).into())
                 .collect(),
         }
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+// сокеты без описания тоже показываются, просто без подсказки
+fn summary(descriptions: &[Description], name: &str) -> &'static str {
+    descriptions
+        .iter()
+        .find(|x| x.name == name)
+        .map_or("", |x| x.summary)
+}
+
+#[derive(PartialEq, Debug, Serialize, Clone)]
 struct NodeInput {
     pub number: usize,
     pub name: String,
     pub description: String,
+    pub signal: SignalType,
+    pub min: f32,
+    pub max: f32,
 }
 
-impl From<(&musicbx::types::NodeInput, &Description)> for NodeInput {
-    fn from((input, desc): (&musicbx::types::NodeInput, &Description)) -> Self {
+impl From<(&musicbx::types::NodeInput, &str)> for NodeInput {
+    fn from((input, summary): (&musicbx::types::NodeInput, &str)) -> Self {
         Self {
             number: input.number,
             name: input.name.to_string(),
-            description: desc.summary.to_string(),
+            description: summary.to_string(),
+            signal: input.signal,
+            min: input.min,
+            max: input.max,
         }
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+#[derive(PartialEq, Debug, Serialize, Clone)]
 struct NodeOutput {
     pub number: usize,
     pub name: String,
     pub description: String,
+    pub signal: SignalType,
+    pub min: f32,
+    pub max: f32,
 }
 
-impl From<(&musicbx::types::NodeOutput, &Description)> for NodeOutput {
-    fn from((input, desc): (&musicbx::types::NodeOutput, &Description)) -> Self {
+impl From<(&musicbx::types::NodeOutput, &str)> for NodeOutput {
+    fn from((input, summary): (&musicbx::types::NodeOutput, &str)) -> Self {
         Self {
             number: input.number,
             name: input.name.to_string(),
-            description: desc.summary.to_string(),
+            description: summary.to_string(),
+            signal: input.signal,
+            min: input.min,
+            max: input.max,
         }
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+#[derive(PartialEq, Debug, Serialize, Clone)]
 struct NodeParameter {
     pub number: usize,
     pub kind: NodeParameterKind,
+    pub min: f32,
+    pub max: f32,
+    pub curve: ParameterCurve,
     pub default: String,
     pub name: String,
+    pub choices: Vec<String>,
     pub description: String,
 }
 
-impl From<(&musicbx::types::NodeParameter, &Description)> for NodeParameter {
-    fn from((param, desc): (&musicbx::types::NodeParameter, &Description)) -> Self {
+impl From<(&musicbx::types::NodeParameter, &str)> for NodeParameter {
+    fn from((param, summary): (&musicbx::types::NodeParameter, &str)) -> Self {
         Self {
             number: param.number,
             kind: param.kind,
+            min: param.min,
+            max: param.max,
+            curve: param.curve,
             default: param.default.to_string(),
             name: param.name.to_string(),
-            description: desc.summary.to_string(),
+            choices: param.choices.iter().map(ToString::to_string).collect(),
+            description: summary.to_string(),
         }
     }
 }
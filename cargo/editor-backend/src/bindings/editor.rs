@@ -1,3 +1,6 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
 use jni::objects::{JClass, JString};
 use jni::sys::{jfloat, jint};
 use jni::JNIEnv;
@@ -135,3 +138,18 @@ pub extern "system" fn Java_ru_pema4_musicbx_service_NativeEditorService_setPara
     };
     App::current().accept_message(msg);
 }
+
+#[no_mangle]
+pub extern "system" fn Java_ru_pema4_musicbx_service_NativeEditorService_renderToWav(
+    env: JNIEnv,
+    _: JClass,
+    path: JString,
+    duration_seconds: jfloat,
+) {
+    let path: String = unwrap_or_throw!(env, env.get_string(path)).into();
+    let msg = AppMsg::RenderToWav {
+        path: PathBuf::from(path),
+        duration: unwrap_or_throw!(env, Duration::try_from_secs_f32(duration_seconds)),
+    };
+    App::current().accept_message(msg);
+}
@@ -1,16 +1,22 @@
 mod available_nodes;
 mod configuration;
 mod editor;
+mod parameters;
 
+/// Бросает IllegalArgumentException и выходит из JNI-функции, возвращая `$default`.
+/// Паниковать здесь нельзя: паника не должна раскручиваться через границу FFI
 #[macro_export]
 macro_rules! unwrap_or_throw {
     ($env:expr, $result:expr) => {
+        $crate::unwrap_or_throw!($env, $result, ())
+    };
+    ($env:expr, $result:expr, $default:expr) => {
         match $result {
             Ok(v) => v,
             Err(err) => {
-                $env.throw_new("java/lang/IllegalArgumentException", err.to_string())
-                    .unwrap();
-                return;
+                // если не удалось даже бросить исключение, JVM уже в состоянии ошибки
+                let _ = $env.throw_new("java/lang/IllegalArgumentException", err.to_string());
+                return $default;
             }
         }
     };
@@ -0,0 +1,68 @@
+use jni::objects::{JClass, JString};
+use jni::sys::{jfloat, jstring};
+use jni::JNIEnv;
+use serde::Deserialize;
+
+use musicbx::types::{NodeParameterKind, ParameterCurve};
+
+use crate::unwrap_or_throw;
+
+// редактор присылает параметр в том же виде, в каком получил его из списка узлов,
+// поэтому единицы измерения и кривые есть только здесь
+#[derive(Debug, Deserialize)]
+struct Parameter {
+    kind: NodeParameterKind,
+    min: f32,
+    max: f32,
+    curve: ParameterCurve,
+    #[serde(default)]
+    choices: Vec<String>,
+}
+
+impl Parameter {
+    fn choices(&self) -> Vec<&str> {
+        self.choices.iter().map(String::as_str).collect()
+    }
+
+    fn format(&self, normalized: f32) -> String {
+        let value = self.curve.denormalize(self.min, self.max, normalized);
+        self.kind.format_value(value, &self.choices())
+    }
+
+    fn parse(&self, text: &str) -> anyhow::Result<f32> {
+        let value = self.kind.parse_value(text, &self.choices())?;
+        Ok(self.curve.normalize(self.min, self.max, value))
+    }
+}
+
+fn parse_parameter(env: JNIEnv, parameter: JString) -> anyhow::Result<Parameter> {
+    let parameter: String = env.get_string(parameter)?.into();
+    Ok(serde_json::from_str(&parameter)?)
+}
+
+#[no_mangle]
+pub extern "system" fn Java_ru_pema4_musicbx_service_NativeParameterFormatService_formatParameter(
+    env: JNIEnv,
+    _: JClass,
+    parameter: JString,
+    normalized: jfloat,
+) -> jstring {
+    let text = parse_parameter(env, parameter)
+        .map(|parameter| parameter.format(normalized))
+        .and_then(|text| Ok(env.new_string(text)?));
+    unwrap_or_throw!(env, text, std::ptr::null_mut()).into_inner()
+}
+
+/// NaN, если текст не удалось разобрать
+#[no_mangle]
+pub extern "system" fn Java_ru_pema4_musicbx_service_NativeParameterFormatService_parseParameter(
+    env: JNIEnv,
+    _: JClass,
+    parameter: JString,
+    text: JString,
+) -> jfloat {
+    let text: anyhow::Result<String> = env.get_string(text).map(Into::into).map_err(Into::into);
+    parse_parameter(env, parameter)
+        .and_then(|parameter| parameter.parse(&text?))
+        .unwrap_or(f32::NAN)
+}
@@ -10,6 +10,7 @@ pub struct DeviceConfiguration {
     pub current: Option<String>,
     pub available: Vec<String>,
     pub sample_rate: Option<SampleRateConfiguration>,
+    pub channels: Option<ChannelsConfiguration>,
 }
 
 #[derive(Default, PartialEq, Debug, Serialize, Deserialize)]
@@ -17,3 +18,9 @@ pub struct SampleRateConfiguration {
     pub current: u32,
     pub available: Vec<u32>,
 }
+
+#[derive(Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct ChannelsConfiguration {
+    pub current: u16,
+    pub available: Vec<u16>,
+}
@@ -0,0 +1,138 @@
+use additional_nodes::test_fm::TestFm;
+use musicbx::std::{filter, osc, util};
+
+use crate::nodes::{Description, NodeDescription};
+
+pub const ADD: NodeDescription = NodeDescription {
+    node: Description::new("Add", "Sums two signals"),
+    inputs: &[
+        Description::new("a", "The first signal to be added"),
+        Description::new("b", "The second signal to be added"),
+    ],
+    outputs: &[Description::new("output", "The sum signal")],
+    parameters: &[Description::new("b", "Another signal")],
+};
+
+pub const AMP: NodeDescription = NodeDescription {
+    node: Description::new("Amp", "Amplifies the signal"),
+    inputs: &[Description::new("input", "The input of the amplifier")],
+    outputs: &[Description::new("output", "The amplified signal")],
+    parameters: &[Description::new("db", "Amplitude in decibels")],
+};
+
+pub const CONST_SIG: NodeDescription = NodeDescription {
+    node: Description::new("Const", "Outputs a constant signal"),
+    inputs: &[],
+    outputs: &[Description::new("output", "The constant signal")],
+    parameters: &[Description::new("value", "The value of the signal")],
+};
+
+pub const FREQ_TO_PITCH: NodeDescription = NodeDescription {
+    node: Description::new("Freq to Pitch", "Converts a frequency in Hz to a MIDI note"),
+    inputs: &[Description::new("input", "Frequency in Hz")],
+    outputs: &[Description::new("output", "MIDI note, A4 is 69")],
+    parameters: &[],
+};
+
+pub const HARD_CLIP: NodeDescription = NodeDescription {
+    node: Description::new("Hard Clip", "Clips all signal below -1 or above 1"),
+    inputs: &[Description::new("input", "The input of the clipper")],
+    outputs: &[Description::new("output", "The amplified signal")],
+    parameters: &[],
+};
+
+pub const LFO: NodeDescription = NodeDescription {
+    node: Description::new("LFO", "Low frequency oscillator for modulation"),
+    inputs: &[],
+    outputs: &[Description::new("output", "The modulation signal")],
+    parameters: &[
+        Description::new("rate", "Frequency"),
+        Description::new("waveform", "The shape of the signal"),
+        Description::new("bipolar", "Oscillate from -1 to 1 instead of from 0 to 1"),
+    ],
+};
+
+pub const LP12_FILTER: NodeDescription = NodeDescription {
+    node: Description::new("LP12", "12 dB/oct low-pass filter"),
+    inputs: &[Description::new("input", "The input of the filter")],
+    outputs: &[Description::new("out", "The filtered signal")],
+    parameters: &[
+        Description::new("cutoff", "Cutoff frequency"),
+        Description::new("q", "Resonance"),
+    ],
+};
+
+pub const MUL: NodeDescription = NodeDescription {
+    node: Description::new("Mul", "Multiplies two signals"),
+    inputs: &[
+        Description::new("a", "The first signal to be multiplied"),
+        Description::new("b", "The second signal to be multiplied"),
+    ],
+    outputs: &[Description::new("output", "The multiplied signal")],
+    parameters: &[Description::new("b", "Magnitude")],
+};
+
+pub const NOISE: NodeDescription = NodeDescription {
+    node: Description::new("Noise", "Uniform noise generator (from -1.0 to 1.0)"),
+    inputs: &[],
+    outputs: &[Description::new("output", "Generated random signal")],
+    parameters: &[],
+};
+
+pub const PITCH_TO_FREQ: NodeDescription = NodeDescription {
+    node: Description::new("Pitch to Freq", "Converts a MIDI note to a frequency in Hz"),
+    inputs: &[Description::new("input", "MIDI note, A4 is 69")],
+    outputs: &[Description::new("output", "Frequency in Hz")],
+    parameters: &[],
+};
+
+pub const SIMPLE_SAW: NodeDescription = NodeDescription {
+    node: Description::new("Simple Saw", "Simple aliased sawtooth oscillator"),
+    inputs: &[Description::new("tune", "The pitch modulation")],
+    outputs: &[Description::new("output", "The output of the oscillator")],
+    parameters: &[Description::new("freq", "Frequency")],
+};
+
+pub const SIN: NodeDescription = NodeDescription {
+    node: Description::new("Sin", "The sine oscillator with customizable frequency"),
+    inputs: &[
+        Description::new("phase_mod", "Phase modulation of the oscillator"),
+        Description::new("tune", "Tuning of the oscillator"),
+    ],
+    outputs: &[Description::new("output", "The output of the oscillator")],
+    parameters: &[Description::new("freq", "Frequency")],
+};
+
+pub const TEST_FM: NodeDescription = NodeDescription {
+    node: Description::new("Test FM", "Test generated FM patch"),
+    inputs: &[],
+    outputs: &[Description::new("output", "The output of the node")],
+    parameters: &[],
+};
+
+static DESCRIPTIONS: &[(&str, NodeDescription)] = &[
+    (filter::LP12Filter::definition().uid, LP12_FILTER),
+    (osc::Lfo::definition().uid, LFO),
+    (osc::SimpleSawOsc::definition().uid, SIMPLE_SAW),
+    (osc::SinOsc::definition().uid, SIN),
+    (util::Add::definition().uid, ADD),
+    (util::Amp::definition().uid, AMP),
+    (util::ConstSig::definition().uid, CONST_SIG),
+    (util::FreqToPitch::definition().uid, FREQ_TO_PITCH),
+    (util::HardClip::definition().uid, HARD_CLIP),
+    (util::Mul::definition().uid, MUL),
+    (util::PitchToFreq::definition().uid, PITCH_TO_FREQ),
+    (util::UniformRandom::definition().uid, NOISE),
+    (TestFm::definition().uid, TEST_FM),
+];
+
+/// Для узлов без описания имя берётся из последней части uid, а сокеты остаются без пояснений
+pub fn for_uid(uid: &'static str) -> NodeDescription {
+    match DESCRIPTIONS.iter().find(|(x, _)| *x == uid) {
+        Some((_, description)) => *description,
+        None => NodeDescription {
+            node: Description::new(uid.rsplit("::").next().unwrap_or(uid), ""),
+            ..Default::default()
+        },
+    }
+}
@@ -0,0 +1,83 @@
+use std::marker::PhantomData;
+
+use musicbx::dynamic::DynamicModule;
+use musicbx::types::NodeDefinition;
+use musicbx::{DynNode, FromSampleRate};
+
+use crate::nodes::{descriptions, NodeDescription, NodeFactory, NodeInfo};
+
+pub struct DynNodeFactory<T> {
+    info: NodeInfo,
+    _node: PhantomData<fn() -> T>,
+}
+
+impl<T> DynNodeFactory<T>
+where
+    T: DynNode + FromSampleRate + 'static,
+{
+    pub fn boxed(definition: NodeDefinition, description: NodeDescription) -> Box<dyn NodeFactory> {
+        Box::new(DynNodeFactory::<T> {
+            info: NodeInfo {
+                definition,
+                description,
+            },
+            _node: PhantomData,
+        })
+    }
+}
+
+impl<T> NodeFactory for DynNodeFactory<T>
+where
+    T: DynNode + FromSampleRate + 'static,
+{
+    fn uid(&self) -> &str {
+        self.info.definition.uid
+    }
+
+    fn info(&self) -> &NodeInfo {
+        &self.info
+    }
+
+    fn create_instance(&self, sr: f32) -> Option<Box<dyn DynNode>> {
+        Some(Box::new(T::from_sample_rate(sr)))
+    }
+}
+
+/// Узел, который создаёт модуль по своему uid
+pub struct ModuleNodeFactory<M> {
+    module: M,
+    info: NodeInfo,
+}
+
+impl<M> ModuleNodeFactory<M>
+where
+    M: DynamicModule + 'static,
+{
+    pub fn boxed(module: M, definition: NodeDefinition) -> Box<dyn NodeFactory> {
+        let description = descriptions::for_uid(definition.uid);
+        Box::new(ModuleNodeFactory {
+            module,
+            info: NodeInfo {
+                definition,
+                description,
+            },
+        })
+    }
+}
+
+impl<M> NodeFactory for ModuleNodeFactory<M>
+where
+    M: DynamicModule,
+{
+    fn uid(&self) -> &str {
+        self.info.definition.uid
+    }
+
+    fn info(&self) -> &NodeInfo {
+        &self.info
+    }
+
+    fn create_instance(&self, sr: f32) -> Option<Box<dyn DynNode>> {
+        self.module.create_node(self.info.definition.uid, sr)
+    }
+}
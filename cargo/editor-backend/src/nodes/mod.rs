@@ -1,49 +1,54 @@
-use glicol_synth::AudioContext;
-use petgraph::graph::NodeIndex;
 use serde::Serialize;
 
-pub use add::AddNodeDescription;
-pub use amp::AmpNodeDescription;
-pub use hard_clip::HardClipNodeDescription;
-pub use mul::MulNodeDescription;
-use musicbx::types::{NodeDefinition, NodeParameterKind};
-pub use noise::NoiseNodeDescription;
+pub use factory::{DynNodeFactory, ModuleNodeFactory};
+use musicbx::dynamic::DynamicModule;
+use musicbx::types::{ModuleDefinition, NodeDefinition};
+use musicbx::DynNode;
 pub use output::OutputNodeDescription;
-pub use simple_saw::SimpleSawNodeDescription;
-pub use sin::SinNodeDescription;
-pub use test_fm::TestFmNodeDescription;
 
-mod add;
-mod amp;
-mod hard_clip;
-mod mul;
-mod noise;
+pub mod descriptions;
+mod factory;
 mod output;
-mod simple_saw;
-mod sin;
-mod test_fm;
 
 pub trait NodeFactory {
     fn uid(&self) -> &str;
     fn info(&self) -> &NodeInfo;
-    fn create_instance(&self, id: usize) -> Box<dyn NodeWrapper>;
+    // у синтетических узлов нет собственной обработки
+    fn create_instance(&self, sr: f32) -> Option<Box<dyn DynNode>>;
 }
 
-pub trait NodeWrapper {
-    fn id(&self) -> usize;
-    fn input(&self, name: &str) -> Option<(usize, NodeIndex)>;
-    fn output(&self, name: &str) -> Option<NodeIndex>;
-    fn add_to_context(&mut self, context: &mut AudioContext<1>);
-    fn set_parameter(&self, _context: &mut AudioContext<1>, _index: u8, _value: f32) {}
+/// Доступные в редакторе узлы как модуль для построения графа из патча
+pub struct NodeFactories<'a>(pub &'a [Box<dyn NodeFactory>]);
+
+impl NodeFactories<'_> {
+    fn find(&self, uid: &str) -> Option<&dyn NodeFactory> {
+        self.0.iter().find(|x| x.uid() == uid).map(|x| x.as_ref())
+    }
+}
+
+impl ModuleDefinition for NodeFactories<'_> {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        self.0.iter().map(|x| &x.info().definition).collect()
+    }
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        Some(&self.find(uid)?.info().definition)
+    }
+}
+
+impl DynamicModule for NodeFactories<'_> {
+    fn create_node(&self, uid: &str, sr: f32) -> Option<Box<dyn DynNode>> {
+        self.find(uid)?.create_instance(sr)
+    }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Default)]
+#[derive(PartialEq, Debug, Serialize, Default)]
 pub struct NodeInfo {
     pub definition: NodeDefinition,
     pub description: NodeDescription,
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Default)]
+#[derive(PartialEq, Eq, Debug, Serialize, Default, Clone, Copy)]
 pub struct NodeDescription {
     pub node: Description,
     pub inputs: &'static [Description],
@@ -51,7 +56,7 @@ pub struct NodeDescription {
     pub parameters: &'static [Description],
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Default)]
+#[derive(PartialEq, Eq, Debug, Serialize, Default, Clone, Copy)]
 pub struct Description {
     pub name: &'static str,
     pub summary: &'static str,
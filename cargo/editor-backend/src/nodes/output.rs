@@ -1,9 +1,8 @@
-use glicol_synth::AudioContext;
-use petgraph::graph::NodeIndex;
+use musicbx::types::patch::SYNTHETIC_OUTPUT_UID;
+use musicbx::types::{NodeDefinition, NodeInput, SignalType};
+use musicbx::DynNode;
 
-use musicbx::types::{NodeDefinition, NodeInput};
-
-use crate::nodes::{Description, NodeDescription, NodeFactory, NodeInfo, NodeWrapper};
+use crate::nodes::{Description, NodeDescription, NodeFactory, NodeInfo};
 
 pub struct OutputNodeDescription;
 
@@ -16,54 +15,48 @@ impl NodeFactory for OutputNodeDescription {
         &INFO
     }
 
-    fn create_instance(&self, id: usize) -> Box<dyn NodeWrapper> {
-        Box::new(OutputNode {
-            id,
-            node_index: None,
-        })
+    fn create_instance(&self, _: f32) -> Option<Box<dyn DynNode>> {
+        None
     }
 }
 
 static INFO: NodeInfo = NodeInfo {
     definition: NodeDefinition {
-        uid: "_synthetic_output",
-        inputs: &[NodeInput {
-            number: 0,
-            name: "input",
-        }],
+        uid: SYNTHETIC_OUTPUT_UID,
+        inputs: &[
+            NodeInput {
+                number: 0,
+                name: "input",
+                signal: SignalType::Audio,
+                min: SignalType::Audio.min(),
+                max: SignalType::Audio.max(),
+            },
+            NodeInput {
+                number: 1,
+                name: "channel_0",
+                signal: SignalType::Audio,
+                min: SignalType::Audio.min(),
+                max: SignalType::Audio.max(),
+            },
+            NodeInput {
+                number: 2,
+                name: "channel_1",
+                signal: SignalType::Audio,
+                min: SignalType::Audio.min(),
+                max: SignalType::Audio.max(),
+            },
+        ],
         outputs: &[],
         parameters: &[],
     },
     description: NodeDescription {
         node: Description::new("Output", "The output node"),
-        inputs: &[Description::new("input", "The mono input")],
+        inputs: &[
+            Description::new("input", "The mono input, sent to every channel"),
+            Description::new("channel_0", "The left channel"),
+            Description::new("channel_1", "The right channel"),
+        ],
         outputs: &[],
         parameters: &[],
     },
 };
-
-struct OutputNode {
-    id: usize,
-    node_index: Option<NodeIndex>,
-}
-
-impl NodeWrapper for OutputNode {
-    fn id(&self) -> usize {
-        self.id
-    }
-
-    fn input(&self, name: &str) -> Option<(usize, NodeIndex)> {
-        match name {
-            "input" => self.node_index.map(|x| (0, x)),
-            _ => None,
-        }
-    }
-
-    fn output(&self, _: &str) -> Option<NodeIndex> {
-        None
-    }
-
-    fn add_to_context(&mut self, context: &mut AudioContext<1>) {
-        self.node_index = Some(context.destination);
-    }
-}
@@ -20,7 +20,7 @@ impl<T> Observable<T> {
         listener(&self.data)
     }
 
-    #[allow(clippy::vtable_address_comparisons, dead_code)]
+    #[allow(dead_code)]
     pub fn remove_listener(&self, listener: &Listener<T>) {
         let listeners = &mut self.listeners.lock().unwrap();
         listeners.retain(|x| !Listener::ptr_eq(x, listener));
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use clap::{Parser, Subcommand};
+
+use musicbx::codegen::MusicbxCodegen;
+use musicbx::dynamic::{render_patch_to_wav, Graph, PatchModule};
+use musicbx::std::StdModuleDefinition;
+use musicbx::types::patch::Patch;
+use musicbx::types::{signal_warnings, validate, ModuleDefinition, NodeDefinition};
+
+mod play;
+
+const DEFAULT_SAMPLE_RATE: u32 = 48000;
+
+/// Play, render and inspect musicbx patches without the editor
+#[derive(Parser)]
+#[command(name = "musicbx", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// A patch that other patches can use as a node by its file name
+    #[arg(long = "subpatch", global = true)]
+    subpatches: Vec<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check that a patch can be built from the std nodes and subpatches
+    Validate {
+        patch: PathBuf,
+
+        /// Sample rate in Hz the graph is built with
+        #[arg(long, default_value_t = DEFAULT_SAMPLE_RATE)]
+        sr: u32,
+    },
+
+    /// Render a patch to a 32-bit float WAV file
+    Render {
+        patch: PathBuf,
+
+        #[arg(short, long)]
+        out: PathBuf,
+
+        /// Duration in seconds
+        #[arg(short, long, default_value_t = 10.0)]
+        duration: f32,
+
+        /// Sample rate in Hz
+        #[arg(long, default_value_t = DEFAULT_SAMPLE_RATE)]
+        sr: u32,
+
+        #[arg(long, default_value_t = 2)]
+        channels: u16,
+    },
+
+    /// Play a patch on an audio output
+    Play {
+        patch: PathBuf,
+
+        /// Output device name, the default output is used if omitted
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Duration in seconds, plays until interrupted if omitted
+        #[arg(short, long)]
+        duration: Option<f32>,
+    },
+
+    /// Generate Rust code for patches the same way a build script does, one `.rs` file per patch
+    Codegen {
+        #[arg(required = true)]
+        patches: Vec<PathBuf>,
+
+        /// Directory for the generated files
+        #[arg(short, long, default_value = ".")]
+        out_dir: PathBuf,
+    },
+
+    /// List available node uids with their inputs, outputs and parameters, including subpatches
+    List,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let mut subpatches = BTreeMap::new();
+    for path in &cli.subpatches {
+        subpatches.insert(patch_name(path)?, read_patch(path)?);
+    }
+    let module = PatchModule::new(&StdModuleDefinition, subpatches)?;
+
+    match cli.command {
+        Command::Validate { patch, sr } => {
+            let patch = read_patch(&patch)?;
+            check_patch(&patch, &module)?;
+            Graph::from_patch(&patch, &module, sr as f32)?;
+            println!(
+                "OK: {} nodes, {} cables",
+                patch.nodes.len(),
+                patch.cables.len()
+            );
+        }
+        Command::Render {
+            patch,
+            out,
+            duration,
+            sr,
+            channels,
+        } => {
+            let patch = read_patch(&patch)?;
+            check_patch(&patch, &module)?;
+            render_patch_to_wav(
+                &patch,
+                &module,
+                sr,
+                channels,
+                parse_duration(duration)?,
+                &out,
+            )?;
+        }
+        Command::Play {
+            patch,
+            output,
+            duration,
+        } => {
+            let patch = read_patch(&patch)?;
+            check_patch(&patch, &module)?;
+            let duration = duration.map(parse_duration).transpose()?;
+            play::play(&patch, &module, output.as_deref(), duration)?;
+        }
+        Command::Codegen { patches, out_dir } => {
+            // сабпатчи тоже генерируются, иначе сгенерированный код не на что сослать
+            let mut codegen =
+                MusicbxCodegen::with_output_dir(&out_dir).module(&StdModuleDefinition);
+            for path in patches.iter().chain(&cli.subpatches) {
+                codegen.input(path);
+            }
+            codegen.run()?;
+        }
+        Command::List => {
+            for definition in module.definitions() {
+                print_definition(definition);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn read_patch(path: &Path) -> anyhow::Result<Patch> {
+    let json = fs::read_to_string(path)
+        .with_context(|| format!("Can't read patch from {}", path.display()))?;
+    let patch = serde_json::from_str(&json)
+        .with_context(|| format!("Can't parse patch from {}", path.display()))?;
+    Ok(patch)
+}
+
+fn check_patch(patch: &Patch, module: &dyn ModuleDefinition) -> anyhow::Result<()> {
+    let diagnostics = validate(patch, module);
+    for diagnostic in &diagnostics {
+        eprintln!("error: {diagnostic}");
+    }
+
+    if !diagnostics.is_empty() {
+        bail!("The patch has {} problem(s)", diagnostics.len());
+    }
+
+    // патч с предупреждениями всё равно собирается
+    for warning in signal_warnings(patch, module) {
+        eprintln!("warning: {warning}");
+    }
+    Ok(())
+}
+
+fn patch_name(path: &Path) -> anyhow::Result<String> {
+    path.file_stem()
+        .and_then(|x| x.to_str())
+        .map(ToString::to_string)
+        .ok_or_else(|| anyhow!("Invalid patch file name {}", path.display()))
+}
+
+fn parse_duration(seconds: f32) -> anyhow::Result<Duration> {
+    Duration::try_from_secs_f32(seconds).with_context(|| format!("Invalid duration {seconds}"))
+}
+
+fn print_definition(definition: &NodeDefinition) {
+    println!("{}", definition.uid);
+
+    let inputs: Vec<_> = definition
+        .inputs
+        .iter()
+        .map(|x| format!("{} ({:?} {}..{})", x.name, x.signal, x.min, x.max))
+        .collect();
+    println!("  inputs: {}", inputs.join(", "));
+
+    let outputs: Vec<_> = definition
+        .outputs
+        .iter()
+        .map(|x| format!("{} ({:?} {}..{})", x.name, x.signal, x.min, x.max))
+        .collect();
+    println!("  outputs: {}", outputs.join(", "));
+
+    let parameters: Vec<_> = definition
+        .parameters
+        .iter()
+        .map(|x| {
+            let choices = if x.choices.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", x.choices.join(", "))
+            };
+            let default = match x.kind.parse_value(x.default, x.choices) {
+                Ok(default) => x.kind.format_value(default, x.choices),
+                Err(_) => x.default.to_string(),
+            };
+            format!(
+                "{} ({:?}{choices} {}..{} {:?}, default {default})",
+                x.name,
+                x.kind,
+                x.kind.format_value(x.min, x.choices),
+                x.kind.format_value(x.max, x.choices),
+                x.curve,
+            )
+        })
+        .collect();
+    println!("  parameters: {}", parameters.join(", "));
+}
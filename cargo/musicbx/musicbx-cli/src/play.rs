@@ -0,0 +1,87 @@
+use std::thread;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{Device, OutputCallbackInfo, Sample, SampleFormat, Stream, StreamConfig};
+
+use musicbx::dynamic::{BlockStream, DynamicModule, Graph};
+use musicbx::types::patch::Patch;
+use musicbx::AudioBuffer;
+
+const BLOCK_SIZE: usize = 128;
+
+pub fn play(
+    patch: &Patch,
+    module: &dyn DynamicModule,
+    output: Option<&str>,
+    duration: Option<Duration>,
+) -> anyhow::Result<()> {
+    let device = find_output(output)?;
+    let config = device.default_output_config()?;
+    let graph = Graph::from_patch(patch, module, config.sample_rate().0 as f32)?;
+
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => start_audio_stream::<f32>(graph, &device, &config.into()),
+        SampleFormat::I16 => start_audio_stream::<i16>(graph, &device, &config.into()),
+        SampleFormat::U16 => start_audio_stream::<u16>(graph, &device, &config.into()),
+    }?;
+    stream.play()?;
+
+    eprintln!("Playing on {}", device.name()?);
+    match duration {
+        Some(duration) => thread::sleep(duration),
+        // поток cpal играет, пока процесс не прервут
+        None => loop {
+            thread::park();
+        },
+    }
+
+    Ok(())
+}
+
+fn find_output(name: Option<&str>) -> anyhow::Result<Device> {
+    let host = cpal::default_host();
+
+    let name = match name {
+        Some(name) => name,
+        None => {
+            return host
+                .default_output_device()
+                .ok_or_else(|| anyhow!("No default output device"))
+        }
+    };
+
+    let mut available = vec![];
+    for device in host.output_devices()? {
+        let device_name = device.name()?;
+        if device_name == name {
+            return Ok(device);
+        }
+        available.push(device_name);
+    }
+
+    Err(anyhow!(
+        "Unknown output {name:?}, available outputs: {}",
+        available.join(", ")
+    ))
+}
+
+fn start_audio_stream<T: Sample>(
+    mut graph: Graph,
+    device: &Device,
+    config: &StreamConfig,
+) -> anyhow::Result<Stream> {
+    let mut blocks = BlockStream::new(config.channels as usize, BLOCK_SIZE);
+
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &OutputCallbackInfo| {
+            let process = |n, block: &mut AudioBuffer| graph.process(n, block);
+            blocks.fill(data, process, |x| Sample::from::<f32>(&x));
+        },
+        |err| eprintln!("an error occurred on stream: {:?}", err),
+    )?;
+
+    Ok(stream)
+}
@@ -1,20 +1,27 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::{fs, io};
+use std::{env, fs, io};
 
 use itertools::Itertools;
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use thiserror::Error;
 
-use musicbx_types::patch::{Cable, Node, Patch};
-use musicbx_types::ModuleDefinition;
+use musicbx_types::patch::{
+    Cable, CableEnd, GridOffset, Node, Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID,
+};
+use musicbx_types::{
+    dependency_order, exposed_parameters, patch_definition, socket_conversion, validate,
+    Diagnostic, ModuleDefinition, NodeDefinition, NodeParameter, SignalConversion, SubpatchError,
+};
 
 #[derive(Default)]
 pub struct MusicbxCodegen {
     modules: Vec<Box<dyn ModuleDefinition>>,
     inputs: Vec<PathBuf>,
     output_dir: PathBuf,
+    // предупреждения из build.rs cargo показывает, только если они в его формате
+    build_script: bool,
 }
 
 #[derive(Debug, Error)]
@@ -43,6 +50,14 @@ pub enum MusicbxExecutionError {
     #[error("Invalid parameter value: {0}")]
     InvalidParameterValue(String),
 
+    #[error("Invalid port name: {0}")]
+    InvalidPortName(String),
+
+    InvalidSubpatch(#[from] SubpatchError),
+
+    #[error("Invalid patch {0}: {}", display_diagnostics(.1))]
+    InvalidPatch(String, Vec<Diagnostic>),
+
     #[error("Should not really happen")]
     InvalidState,
 }
@@ -53,6 +68,7 @@ impl MusicbxCodegen {
             modules: vec![],
             inputs: vec![],
             output_dir: PathBuf::from(output_dir.as_ref()),
+            build_script: false,
         }
     }
 
@@ -71,85 +87,181 @@ impl MusicbxCodegen {
         }
     }
 
-    pub fn input(&mut self, path: &impl AsRef<str>) -> &mut Self {
+    pub fn input(&mut self, path: &impl AsRef<Path>) -> &mut Self {
         self.inputs.push(path.as_ref().into());
         self
     }
 
-    pub fn run(self) -> Result<(), MusicbxCodegenError> {
-        let MusicbxCodegen {
-            output_dir, inputs, ..
-        } = &self;
+    /// Добавляет все `.json` патчи из директории, в порядке имён файлов
+    pub fn input_dir(mut self, dir: &impl AsRef<Path>) -> Result<Self, MusicbxCodegenError> {
+        let mut inputs = Vec::new();
+        for entry in fs::read_dir(dir).map_err(MusicbxExecutionError::from)? {
+            let path = entry.map_err(MusicbxExecutionError::from)?.path();
+            if path.is_file() && path.extension().is_some_and(|x| x == "json") {
+                inputs.push(path);
+            }
+        }
+        inputs.sort();
+
+        self.inputs.extend(inputs);
+        Ok(self)
+    }
 
-        for input in inputs {
-            let mut out_file = output_dir.clone();
-            out_file.push({
-                let input_file = extract_name_from_input_file(input)?;
-                format!("{input_file}.rs")
-            });
-            self.generate(input, &out_file)?;
+    /// Для build.rs: генерирует все патчи из `patch_dir` в OUT_DIR
+    /// и просит cargo перезапустить скрипт, когда патчи меняются
+    pub fn for_build_script(patch_dir: &impl AsRef<Path>) -> Result<Self, MusicbxCodegenError> {
+        let output_dir = env::var_os("OUT_DIR").ok_or(MusicbxCodegenError::NoOutDir)?;
+        let mut codegen = Self::with_output_dir(&output_dir).input_dir(patch_dir)?;
+        codegen.build_script = true;
+
+        // директория отслеживается ради новых и удалённых патчей
+        println!("cargo:rerun-if-changed={}", patch_dir.as_ref().display());
+        for input in &codegen.inputs {
+            println!("cargo:rerun-if-changed={}", input.display());
+        }
+
+        Ok(codegen)
+    }
+
+    /// Узел может ссылаться на другой входной патч по его имени,
+    /// поэтому патчи генерируются в порядке зависимостей
+    pub fn run(mut self) -> Result<(), MusicbxCodegenError> {
+        let mut patches = BTreeMap::new();
+        for input in &self.inputs {
+            let name = extract_name_from_input_file(input)?;
+            patches.insert(name.to_string(), read_patch(input)?);
+        }
+
+        for name in dependency_order(&patches).map_err(MusicbxExecutionError::from)? {
+            let mut out_file = self.output_dir.clone();
+            out_file.push(format!("{name}.rs"));
+
+            let definition = self.generate(name, &patches[name], &out_file)?;
+            // следующие патчи видят уже сгенерированные узлы
+            self.modules.push(Box::new(vec![definition]));
         }
 
         Ok(())
     }
 
-    fn generate(&self, input: &Path, output: &Path) -> Result<(), MusicbxExecutionError> {
-        let node_description = fs::read(input)?;
-        let Patch { nodes, cables } = serde_json::from_slice(&node_description[..])?;
+    fn warn(&self, message: &str) {
+        if self.build_script {
+            println!("cargo:warning={message}");
+        } else {
+            eprintln!("warning: {message}");
+        }
+    }
 
-        let node_name = extract_name_from_input_file(input)?;
-        let node_name = Ident::new(node_name, Span::call_site());
+    fn generate(
+        &self,
+        name: &str,
+        patch: &Patch,
+        output: &Path,
+    ) -> Result<NodeDefinition, MusicbxExecutionError> {
+        let diagnostics = validate(patch, &self.modules.as_slice());
+        if !diagnostics.is_empty() {
+            Err(MusicbxExecutionError::InvalidPatch(
+                name.to_string(),
+                diagnostics,
+            ))?;
+        }
 
-        let nodes: HashMap<usize, Node> = nodes.into_iter().map(|node| (node.id, node)).collect();
+        let definition = patch_definition(name, patch, &self.modules.as_slice())?;
+
+        // узлы, которые не влияют на выходы, не нужно ни хранить, ни обрабатывать
+        let patch = &patch.without_dead_nodes();
+        let patch = &self.with_signal_converters(patch);
+        let Patch { nodes, cables } = patch;
+        // имя файла становится именем типа, а `ring-mod` или `2voice` им быть не могут
+        let node_name: Ident = syn::parse_str(name)
+            .map_err(|_| MusicbxExecutionError::InvalidInputFileName(name.to_string()))?;
+
+        // порядок узлов и параметров не должен зависеть от хешей, иначе файл меняется между сборками
+        let nodes: BTreeMap<usize, &Node> = nodes.iter().map(|node| (node.id, node)).collect();
+        let (idents, warnings) = node_idents(nodes.values().copied());
+        for warning in warnings {
+            self.warn(&format!("{name}: {warning}"));
+        }
 
-        let route_declarations = declare_routing(&nodes, cables.iter())?;
-        let field_declarations = declare_node_fields(nodes.values())?;
+        let parameters = self.extract_parameters(&nodes, &cables[..])?;
+        let route_declarations = declare_routing(&nodes, &idents, cables.iter())?;
+        let field_declarations =
+            declare_node_fields(nodes.values().copied(), &idents, &parameters)?;
 
-        let parameters: HashSet<(usize, &str, String)> =
-            self.extract_parameters(&nodes, &cables[..])?;
-        let parameter_field_declarations: Vec<TokenStream> = parameters
+        // постоянные значения подаются на входы как DataRef::Float, без буфера
+        let parameter_route_declarations: Vec<TokenStream> = parameters
             .iter()
-            .map(|(node_id, param_name, param_value)| {
-                let param_ident = format_ident!("{}_{param_name}", node_ident(*node_id));
-                let param_value: f32 = param_value.parse().unwrap();
-                quote! {
-                    #[from(musicbx::std::util::ConstSig::new(#param_value))]
-                    #param_ident: musicbx::std::util::ConstSig
-                }
+            .map(|x| {
+                let node_ident = &idents[&x.node_id];
+                let node_input = format_ident!("{}", x.name);
+                let value = x.value;
+                quote! { #value -> #node_ident.#node_input }
             })
             .collect();
-        let parameter_route_declarations: Vec<TokenStream> = parameters
+
+        let exposed_declarations: Vec<TokenStream> = definition
+            .parameters
             .iter()
-            .map(|(node_id, param_name, _)| {
-                let param_ident = format_ident!("{}_{param_name}", node_ident(*node_id));
-                let node_ident = node_ident(*node_id);
-                let node_input = format_ident!("{param_name}");
-                quote! { #param_ident.output -> #node_ident.#node_input }
+            .map(|x| {
+                let name = format_ident!("{}", x.name);
+                let kind = format_ident!("{}", format!("{:?}", x.kind));
+                let options = declare_parameter_options(x);
+                let default: f32 = x.default.parse().map_err(|_| {
+                    MusicbxExecutionError::InvalidParameterValue(x.default.to_string())
+                })?;
+                Ok(quote! { #name: #kind #options = #default })
+            })
+            .collect::<Result<_, MusicbxExecutionError>>()?;
+        let exposed_route_declarations: Vec<TokenStream> = exposed_parameters(patch)
+            .into_iter()
+            .map(|(node, parameter, name)| {
+                let name = format_ident!("{name}");
+                let node_ident = &idents[&node.id];
+                let node_input = format_ident!("{parameter}");
+                quote! { #name -> #node_ident.#node_input }
             })
             .collect();
 
-        let generated_code = quote! {
-            #[musicbx::node {
-                #( #route_declarations, )*
-                #( #parameter_route_declarations, )*
-            }]
+        let struct_code = quote! {
             #[derive(musicbx::FromSampleRate)]
             pub struct #node_name {
                 #( #field_declarations, )*
-                #( #parameter_field_declarations, )*
             }
         };
-
-        fs::write(output, generated_code.to_string())?;
-
-        Ok(())
+        let struct_file: syn::File =
+            syn::parse2(struct_code).map_err(|_| MusicbxExecutionError::InvalidState)?;
+
+        // uid сгенерированного узла - имя патча, как и у подпатчей в редакторе и musicbx-dynamic
+        let uid_declaration = vec![quote! { uid = #name }];
+
+        // prettyplease не форматирует аргументы атрибутов, поэтому маршруты выводятся построчно
+        let routing = [
+            uid_declaration,
+            exposed_declarations,
+            route_declarations,
+            parameter_route_declarations,
+            exposed_route_declarations,
+        ]
+        .iter()
+        .flatten()
+        .map(|x| format!("    {},\n", format_routing_entry(x)))
+        .join("");
+
+        let generated_code = format!(
+            "// Generated by musicbx-codegen from patch {name}, do not edit\n\n\
+             #[musicbx::node {{\n{routing}}}]\n{}",
+            prettyplease::unparse(&struct_file),
+        );
+        fs::write(output, generated_code)?;
+
+        Ok(definition)
     }
 
     fn extract_parameters<'a>(
         &self,
-        nodes: &'a HashMap<usize, Node>,
+        nodes: &BTreeMap<usize, &'a Node>,
         routes: &'a [Cable],
-    ) -> Result<HashSet<(usize, &'a str, String)>, MusicbxExecutionError> {
+    ) -> Result<Vec<ConstantParameter<'a>>, MusicbxExecutionError> {
         let overridden_parameters: HashSet<(usize, &str)> = routes
             .iter()
             .map(|cable| &cable.to)
@@ -161,37 +273,146 @@ impl MusicbxCodegen {
             .flat_map(|node| {
                 node.parameters
                     .iter()
+                    .sorted_by_key(|(name, _)| name.as_str())
                     .map(|(name, value)| (node.id, name.as_str(), value.as_str()))
             })
             .filter(|(node_id, name, _)| !overridden_parameters.contains(&(*node_id, *name)))
+            .filter(|(node_id, name, _)| !nodes[node_id].exposed.contains_key(*name))
             .map(|(node_id, param_name, param_value)| {
                 let node = &nodes[&node_id];
-                let node_info = self
-                    .modules
-                    .as_slice()
-                    .info_for_uid(&node.uid)
-                    .ok_or_else(|| InvalidNodeUid(node.uid.to_string()))
-                    .cloned()?;
-                let param_kind = node_info
-                    .parameters
-                    .iter()
-                    .find(|x| x.name == param_name)
-                    .map(|x| &x.kind)
-                    .ok_or_else(|| {
-                        MusicbxExecutionError::UnknownParameterName(
-                            param_name.to_string(),
-                            node.uid.to_string(),
-                        )
-                    })?;
+                let definition = self.parameter_definition(node, param_name)?;
                 let param_value: f32 = param_value.parse().map_err(|_| {
                     MusicbxExecutionError::InvalidParameterValue(param_value.to_string())
                 })?;
 
-                let param_value = param_kind.denormalize(param_value);
-                Ok((node_id, param_name, param_value.to_string()))
+                Ok(ConstantParameter {
+                    node_id,
+                    name: param_name,
+                    value: definition.denormalize(param_value),
+                    display: definition.format(param_value),
+                })
             })
             .collect()
     }
+
+    // высота тона и частота преобразуются узлами std, как это делает динамический граф на кабеле
+    fn with_signal_converters(&self, patch: &Patch) -> Patch {
+        let module = self.modules.as_slice();
+        let definition = |id: usize| {
+            let node = patch.nodes.iter().find(|x| x.id == id)?;
+            module.info_for_uid(&node.uid)
+        };
+
+        let mut result = Patch {
+            nodes: patch.nodes.clone(),
+            cables: vec![],
+        };
+        let mut next_id = patch.nodes.iter().map(|x| x.id + 1).max().unwrap_or(0);
+        for cable in &patch.cables {
+            let conversion = definition(cable.from.node_id)
+                .zip(definition(cable.to.node_id))
+                .and_then(|(from, to)| {
+                    socket_conversion(from, &cable.from.socket_name, to, &cable.to.socket_name)
+                });
+            let converter = match conversion {
+                Some(SignalConversion::PitchToFrequency) => "musicbx::std::util::PitchToFreq",
+                Some(SignalConversion::FrequencyToPitch) => "musicbx::std::util::FreqToPitch",
+                Some(SignalConversion::Identity) | None => {
+                    result.cables.push(cable.clone());
+                    continue;
+                }
+            };
+
+            let id = next_id;
+            next_id += 1;
+            result.nodes.push(Node {
+                id,
+                uid: converter.to_string(),
+                offset: GridOffset { x: 0, y: 0 },
+                parameters: HashMap::new(),
+                collapsed: false,
+                label: None,
+                port: None,
+                exposed: HashMap::new(),
+            });
+            result.cables.push(Cable {
+                from: cable.from.clone(),
+                to: CableEnd {
+                    node_id: id,
+                    socket_name: "input".to_string(),
+                },
+            });
+            result.cables.push(Cable {
+                from: CableEnd {
+                    node_id: id,
+                    socket_name: "output".to_string(),
+                },
+                to: cable.to.clone(),
+            });
+        }
+        result
+    }
+
+    fn parameter_definition(
+        &self,
+        node: &Node,
+        name: &str,
+    ) -> Result<NodeParameter, MusicbxExecutionError> {
+        self.modules
+            .as_slice()
+            .info_for_uid(&node.uid)
+            .ok_or_else(|| InvalidNodeUid(node.uid.to_string()))?
+            .parameters
+            .iter()
+            .find(|x| x.name == name)
+            .cloned()
+            .ok_or_else(|| {
+                MusicbxExecutionError::UnknownParameterName(name.to_string(), node.uid.to_string())
+            })
+    }
+}
+
+fn display_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics.iter().map(ToString::to_string).join("; ")
+}
+
+// диапазон указывается, только если он отличается от диапазона вида параметра
+fn declare_parameter_options(parameter: &NodeParameter) -> Option<TokenStream> {
+    let NodeParameter {
+        kind,
+        min,
+        max,
+        curve,
+        choices,
+        ..
+    } = parameter;
+    let default_range = (kind.min(), kind.max(), kind.curve(*min, *max));
+    if (*min, *max, *curve) == default_range && choices.is_empty() {
+        return None;
+    }
+
+    let curve: TokenStream = format!("{curve:?}").parse().ok()?;
+    Some(quote! { (min = #min, max = #max, curve = #curve, choices = [ #( #choices ),* ]) })
+}
+
+// отрицательные числа бывают только в начале маршрута и после `=`
+fn format_routing_entry(entry: &TokenStream) -> String {
+    let entry = entry
+        .to_string()
+        .replace(" . ", ".")
+        .replace(" : ", ": ")
+        .replace(" , ", ", ")
+        .replace(" (", "(")
+        .replace("= - ", "= -");
+    match entry.strip_prefix("- ") {
+        Some(rest) => format!("-{rest}"),
+        None => entry,
+    }
+}
+
+fn read_patch(input: &Path) -> Result<Patch, MusicbxExecutionError> {
+    let patch = fs::read(input)?;
+    Ok(serde_json::from_slice(&patch[..])?)
 }
 
 fn extract_name_from_input_file(input: &Path) -> Result<&str, MusicbxExecutionError> {
@@ -205,17 +426,36 @@ fn extract_name_from_input_file(input: &Path) -> Result<&str, MusicbxExecutionEr
         })
 }
 
+// постоянный параметр узла: значение для маршрута и его запись с единицами для документации
+struct ConstantParameter<'a> {
+    node_id: usize,
+    name: &'a str,
+    value: f32,
+    display: String,
+}
+
 fn declare_node_fields<'a>(
     nodes: impl IntoIterator<Item = &'a Node>,
+    idents: &HashMap<usize, Ident>,
+    parameters: &[ConstantParameter],
 ) -> Result<Vec<TokenStream>, MusicbxExecutionError> {
     nodes
         .into_iter()
         .map(|x| {
-            let name = node_ident(x.id);
             let ty = get_node_type(&x.uid)?;
 
             Ok(if let NodeType::Node(ty) = ty {
-                Some(quote! { #name : #ty })
+                let name = &idents[&x.id];
+                let doc = format!(" Node {}: {}", x.id, x.uid);
+                let parameter_docs = parameters
+                    .iter()
+                    .filter(|parameter| parameter.node_id == x.id)
+                    .map(|parameter| format!(" - {}: {}", parameter.name, parameter.display));
+                Some(quote! {
+                    #[doc = #doc]
+                    #( #[doc = #parameter_docs] )*
+                    #name : #ty
+                })
             } else {
                 None
             })
@@ -225,6 +465,7 @@ fn declare_node_fields<'a>(
 }
 
 fn declare_route(
+    idents: &HashMap<usize, Ident>,
     from: &Node,
     from_output: &str,
     to: &Node,
@@ -233,19 +474,25 @@ fn declare_route(
     let from_output_ident = format_ident!("{from_output}");
     let from_part: TokenStream = match get_node_type(&from.uid)? {
         NodeType::Output => Err(MusicbxExecutionError::InvalidState)?,
-        NodeType::Input => quote! { #from_output_ident },
+        NodeType::Input => {
+            let port_ident = port_ident(from, from_output)?;
+            quote! { #port_ident }
+        }
         NodeType::Node(_) => {
-            let from_ident = node_ident(from.id);
+            let from_ident = &idents[&from.id];
             quote! { #from_ident . #from_output_ident }
         }
     };
 
     let to_input_ident = format_ident!("{to_input}");
     let to_part: TokenStream = match get_node_type(&to.uid)? {
-        NodeType::Output => quote! { output },
+        NodeType::Output => {
+            let port_ident = port_ident(to, to_input)?;
+            quote! { #port_ident }
+        }
         NodeType::Input => Err(MusicbxExecutionError::InvalidState)?,
         NodeType::Node(_) => {
-            let to_ident = node_ident(to.id);
+            let to_ident = &idents[&to.id];
             quote! { #to_ident . #to_input_ident }
         }
     };
@@ -254,7 +501,8 @@ fn declare_route(
 }
 
 fn declare_routing<'a>(
-    nodes: &HashMap<usize, Node>,
+    nodes: &BTreeMap<usize, &Node>,
+    idents: &HashMap<usize, Ident>,
     cables: impl IntoIterator<Item = &'a Cable>,
 ) -> Result<Vec<TokenStream>, MusicbxExecutionError> {
     cables
@@ -263,13 +511,85 @@ fn declare_routing<'a>(
             let from_node = &nodes[&from.node_id];
             let to_node = &nodes[&to.node_id];
 
-            declare_route(from_node, &from.socket_name, to_node, &to.socket_name)
+            declare_route(
+                idents,
+                from_node,
+                &from.socket_name,
+                to_node,
+                &to.socket_name,
+            )
         })
         .collect::<Result<_, _>>()
 }
 
-fn node_ident(id: usize) -> Ident {
-    Ident::new(&format!("v{id}"), Span::call_site())
+fn port_ident(node: &Node, socket_name: &str) -> Result<Ident, MusicbxExecutionError> {
+    let name = node.port_name(socket_name);
+    syn::parse_str(name).map_err(|_| MusicbxExecutionError::InvalidPortName(name.to_string()))
+}
+
+/// Имена полей для узлов: метка из патча в snake_case, если она есть, иначе `v{id}`.
+/// Метку, из которой не получается имя поля, заменяет `v{id}` с предупреждением
+fn node_idents<'a>(
+    nodes: impl IntoIterator<Item = &'a Node>,
+) -> (HashMap<usize, Ident>, Vec<String>) {
+    let nodes: Vec<&Node> = nodes.into_iter().collect();
+    // метка не должна занять имя `v{id}` другого узла
+    let reserved: HashSet<String> = nodes.iter().map(|x| format!("v{}", x.id)).collect();
+    let mut names = HashSet::new();
+    let mut idents = HashMap::new();
+    let mut warnings = vec![];
+    for node in nodes {
+        let fallback = format!("v{}", node.id);
+        let name = match &node.label {
+            None => fallback,
+            Some(label) => match snake_case(label) {
+                Some(name) => {
+                    let taken =
+                        |x: &String| names.contains(x) || (reserved.contains(x) && *x != fallback);
+                    let mut unique = name.clone();
+                    for n in 2.. {
+                        if !taken(&unique) {
+                            break;
+                        }
+                        unique = format!("{name}_{n}");
+                    }
+                    unique
+                }
+                None => {
+                    warnings.push(format!(
+                        "Node {} label {label:?} is not a valid field name, using {fallback}",
+                        node.id
+                    ));
+                    fallback
+                }
+            },
+        };
+        idents.insert(node.id, format_ident!("{}", name));
+        names.insert(name);
+    }
+    (idents, warnings)
+}
+
+/// `LFO Rate` и `lfoRate` становятся `lfo_rate`. None, если результат не идентификатор:
+/// пустая метка, цифра в начале или ключевое слово
+fn snake_case(label: &str) -> Option<String> {
+    let mut name = String::new();
+    let mut previous: Option<char> = None;
+    for c in label.chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && previous.is_some_and(|x| x.is_lowercase() || x.is_numeric()) {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+        previous = Some(c);
+    }
+
+    let name = name.trim_end_matches('_');
+    syn::parse_str::<Ident>(name).ok()?;
+    Some(name.to_string())
 }
 
 enum NodeType {
@@ -284,8 +604,8 @@ pub struct InvalidNodeUid(String);
 
 fn get_node_type(uid: &str) -> Result<NodeType, InvalidNodeUid> {
     Ok(match uid {
-        "_synthetic_input" => NodeType::Input,
-        "_synthetic_output" => NodeType::Output,
+        SYNTHETIC_INPUT_UID => NodeType::Input,
+        SYNTHETIC_OUTPUT_UID => NodeType::Output,
         uid => {
             let err_fn = || InvalidNodeUid(uid.to_string());
 
@@ -1,3 +1,3 @@
-pub use codegen::{MusicbxCodegen, MusicbxCodegenError};
+pub use codegen::{MusicbxCodegen, MusicbxCodegenError, MusicbxExecutionError};
 
 mod codegen;
@@ -0,0 +1,198 @@
+use std::{env, fs};
+
+use common::{cable, generate, node};
+use musicbx_codegen::{MusicbxCodegen, MusicbxCodegenError, MusicbxExecutionError};
+use musicbx_std::StdModuleDefinition;
+use musicbx_types::patch::{Node, Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+
+mod common;
+
+const EMPTY_PATCH: &str = r#"{ "nodes": [], "cables": [] }"#;
+
+#[test]
+fn file_names_that_are_not_identifiers_are_rejected() {
+    let dir = env::temp_dir().join("musicbx-codegen-invalid-names");
+    fs::create_dir_all(&dir).unwrap();
+
+    for name in ["ring-mod", "2voice", "type"] {
+        let input = dir.join(format!("{name}.json"));
+        fs::write(&input, EMPTY_PATCH).unwrap();
+
+        let mut codegen = MusicbxCodegen::with_output_dir(&dir);
+        codegen.input(&input);
+        match codegen.run() {
+            Err(MusicbxCodegenError::ExecutionError(
+                MusicbxExecutionError::InvalidInputFileName(x),
+            )) => assert_eq!(x, name),
+            other => panic!("Unexpected result for {name}: {other:?}"),
+        }
+    }
+}
+
+// генераторы с метками идут в выход друг за другом
+const LABELED_PATCH: &str = r#"{
+  "nodes": [
+    { "id": 0, "uid": "_synthetic_output", "offset": { "x": 0, "y": 0 }, "parameters": {} },
+    { "id": 1, "uid": "musicbx::std::osc::SinOsc", "offset": { "x": 0, "y": 0 }, "parameters": {}, "label": "LFO Rate" },
+    { "id": 2, "uid": "musicbx::std::osc::SinOsc", "offset": { "x": 0, "y": 0 }, "parameters": {}, "label": "lfoRate" },
+    { "id": 3, "uid": "musicbx::std::osc::SinOsc", "offset": { "x": 0, "y": 0 }, "parameters": {}, "label": "2nd" },
+    { "id": 4, "uid": "musicbx::std::osc::SinOsc", "offset": { "x": 0, "y": 0 }, "parameters": {}, "label": "v3" }
+  ],
+  "cables": [
+    { "from": { "node_id": 1, "socket_name": "output" }, "to": { "node_id": 2, "socket_name": "phase_mod" } },
+    { "from": { "node_id": 2, "socket_name": "output" }, "to": { "node_id": 3, "socket_name": "phase_mod" } },
+    { "from": { "node_id": 3, "socket_name": "output" }, "to": { "node_id": 4, "socket_name": "phase_mod" } },
+    { "from": { "node_id": 4, "socket_name": "output" }, "to": { "node_id": 0, "socket_name": "input" } }
+  ]
+}"#;
+
+#[test]
+fn labels_become_unique_snake_case_fields() {
+    let dir = env::temp_dir().join("musicbx-codegen-labels");
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join("Labeled.json");
+    fs::write(&input, LABELED_PATCH).unwrap();
+
+    let mut codegen = MusicbxCodegen::with_output_dir(&dir).module(&StdModuleDefinition);
+    codegen.input(&input);
+    codegen.run().unwrap();
+
+    let generated = fs::read_to_string(dir.join("Labeled.rs")).unwrap();
+    // у "2nd" нет имени поля, а "v3" не должна занять имя третьего узла
+    for field in ["lfo_rate", "lfo_rate_2", "v3", "v3_2"] {
+        let declaration = format!("    {field}: musicbx::std::osc::SinOsc,");
+        assert!(generated.contains(&declaration), "{field} in {generated}");
+    }
+}
+
+fn port(mut node: Node, name: &str) -> Node {
+    node.port = Some(name.to_string());
+    node
+}
+
+// как RingMod из additional-nodes: два входа и два выхода с именами
+fn ring_mod_patch() -> Patch {
+    Patch {
+        nodes: vec![
+            port(node(0, SYNTHETIC_INPUT_UID), "input"),
+            port(node(1, SYNTHETIC_INPUT_UID), "carrier"),
+            node(2, "musicbx::std::util::Mul"),
+            port(node(3, SYNTHETIC_OUTPUT_UID), "wet"),
+            port(node(4, SYNTHETIC_OUTPUT_UID), "dry"),
+        ],
+        cables: vec![
+            cable(0, "output", 2, "a"),
+            cable(1, "output", 2, "b"),
+            cable(2, "output", 3, "input"),
+            cable(0, "output", 4, "input"),
+        ],
+    }
+}
+
+#[test]
+fn named_ports_become_inputs_and_outputs_of_the_generated_node() {
+    let generated = generate("NamedPorts", &ring_mod_patch()).unwrap();
+
+    for route in [
+        "input -> v2.a",
+        "carrier -> v2.b",
+        "v2.output -> wet",
+        "input -> dry",
+    ] {
+        assert!(
+            generated.contains(&format!("    {route},\n")),
+            "{route} in {generated}"
+        );
+    }
+}
+
+#[test]
+fn port_names_that_are_not_identifiers_are_rejected() {
+    let mut patch = ring_mod_patch();
+    patch.nodes[3].port = Some("wet-out".to_string());
+
+    match generate("InvalidPorts", &patch) {
+        Err(MusicbxCodegenError::ExecutionError(MusicbxExecutionError::InvalidPortName(x))) => {
+            assert_eq!(x, "wet-out")
+        }
+        other => panic!("Unexpected result: {other:?}"),
+    }
+}
+
+#[test]
+fn exposed_parameters_keep_their_range_choices_and_default() {
+    let mut filter = node(1, "musicbx::std::filter::LP12Filter");
+    filter
+        .exposed
+        .insert("q".to_string(), "resonance".to_string());
+    // значение в патче нормализовано и становится значением по умолчанию
+    filter.parameters.insert("q".to_string(), "1".to_string());
+    let mut lfo = node(2, "musicbx::std::osc::Lfo");
+    lfo.exposed
+        .insert("waveform".to_string(), "shape".to_string());
+
+    let patch = Patch {
+        nodes: vec![
+            port(node(0, SYNTHETIC_INPUT_UID), "input"),
+            filter,
+            lfo,
+            node(3, SYNTHETIC_OUTPUT_UID),
+        ],
+        cables: vec![
+            cable(0, "output", 1, "input"),
+            cable(1, "out", 3, "input"),
+            cable(2, "output", 3, "input"),
+        ],
+    };
+    let generated = generate("ExposedRanges", &patch).unwrap();
+
+    for declaration in [
+        "resonance: Number(min = 0.1f32, max = 10f32, curve = Linear, choices = []) = 10f32",
+        "shape: Choice(min = 0f32, max = 3f32, curve = Stepped { steps: 3 }, \
+         choices = [\"Sine\", \"Triangle\", \"Saw\", \"Square\"]) = 0f32",
+        "resonance -> v1.q",
+        "shape -> v2.waveform",
+    ] {
+        assert!(
+            generated.contains(&format!("    {declaration},\n")),
+            "{declaration} in {generated}"
+        );
+    }
+}
+
+#[test]
+fn dead_nodes_are_dropped_and_negative_constants_are_routed_directly() {
+    let mut add = node(1, "musicbx::std::util::Add");
+    add.parameters.insert("b".to_string(), "-0.25".to_string());
+    let mut amp = node(2, "musicbx::std::util::Amp");
+    amp.exposed.insert("db".to_string(), "volume".to_string());
+
+    let patch = Patch {
+        nodes: vec![
+            port(node(0, SYNTHETIC_INPUT_UID), "input"),
+            add,
+            amp,
+            // ни до одного выхода не доходит
+            node(4, "musicbx::std::osc::SinOsc"),
+            node(3, SYNTHETIC_OUTPUT_UID),
+        ],
+        cables: vec![
+            cable(0, "output", 1, "a"),
+            cable(1, "output", 2, "input"),
+            cable(2, "output", 3, "input"),
+            cable(0, "output", 4, "phase_mod"),
+        ],
+    };
+    let generated = generate("DeadNodes", &patch).unwrap();
+
+    for entry in [
+        "-0.25f32 -> v1.b",
+        "volume: Db = -6f32",
+        "volume -> v2.db",
+        "v1: musicbx::std::util::Add",
+    ] {
+        assert!(generated.contains(entry), "{entry} in {generated}");
+    }
+    assert!(!generated.contains("v4"), "{generated}");
+    assert!(!generated.contains("ConstSig"), "{generated}");
+}
@@ -0,0 +1,48 @@
+// у каждого файла с тестами своя копия модуля, и не все используют все функции
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::{env, fs};
+
+use musicbx_codegen::{MusicbxCodegen, MusicbxCodegenError};
+use musicbx_std::StdModuleDefinition;
+use musicbx_types::patch::{Cable, CableEnd, GridOffset, Node, Patch};
+
+pub fn node(id: usize, uid: &str) -> Node {
+    Node {
+        id,
+        uid: uid.to_string(),
+        offset: GridOffset { x: 0, y: 0 },
+        parameters: HashMap::new(),
+        collapsed: false,
+        label: None,
+        port: None,
+        exposed: HashMap::new(),
+    }
+}
+
+pub fn cable(from: usize, output: &str, to: usize, input: &str) -> Cable {
+    let end = |node_id, socket_name: &str| CableEnd {
+        node_id,
+        socket_name: socket_name.to_string(),
+    };
+    Cable {
+        from: end(from, output),
+        to: end(to, input),
+    }
+}
+
+/// Генерирует узел из патча `name` с узлами std и возвращает сгенерированный код
+pub fn generate(name: &str, patch: &Patch) -> Result<String, MusicbxCodegenError> {
+    // у каждого теста своя директория, тесты идут параллельно
+    let dir = env::temp_dir().join(format!("musicbx-codegen-{name}"));
+    fs::create_dir_all(&dir).unwrap();
+    let input = dir.join(format!("{name}.json"));
+    fs::write(&input, serde_json::to_vec(patch).unwrap()).unwrap();
+
+    let mut codegen = MusicbxCodegen::with_output_dir(&dir).module(&StdModuleDefinition);
+    codegen.input(&input);
+    codegen.run()?;
+
+    Ok(fs::read_to_string(dir.join(format!("{name}.rs"))).unwrap())
+}
@@ -0,0 +1,46 @@
+/// Многоканальный блок сэмплов, каналы хранятся друг за другом
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioBuffer {
+    channels: usize,
+    len: usize,
+    samples: Vec<f32>,
+}
+
+impl AudioBuffer {
+    pub fn new(channels: usize, len: usize) -> Self {
+        AudioBuffer {
+            channels,
+            len,
+            samples: vec![0.0; channels * len],
+        }
+    }
+
+    pub fn channels(&self) -> usize {
+        self.channels
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn channel(&self, index: usize) -> &[f32] {
+        &self.samples[index * self.len..(index + 1) * self.len]
+    }
+
+    pub fn channel_mut(&mut self, index: usize) -> &mut [f32] {
+        &mut self.samples[index * self.len..(index + 1) * self.len]
+    }
+
+    pub fn fill(&mut self, value: f32) {
+        self.samples.fill(value);
+    }
+
+    /// Сэмплы одного момента времени по всем каналам
+    pub fn frame(&self, index: usize) -> impl Iterator<Item = f32> + '_ {
+        (0..self.channels).map(move |channel| self.channel(channel)[index])
+    }
+}
@@ -6,6 +6,16 @@ pub enum DataRef<'a> {
     Float(f32),
 }
 
+impl<'a> DataRef<'a> {
+    /// Те же данные, начиная с сэмпла `offset`
+    pub fn offset(self, offset: usize) -> DataRef<'a> {
+        match self {
+            DataRef::Audio(floats) => DataRef::Audio(&floats[offset..]),
+            DataRef::Float(float) => DataRef::Float(float),
+        }
+    }
+}
+
 impl<'a> From<&'a [f32]> for DataRef<'a> {
     fn from(audio: &'a [f32]) -> Self {
         Self::Audio(audio)
@@ -47,6 +57,16 @@ pub enum DataMut<'a> {
     Float(f32),
 }
 
+impl<'a> DataMut<'a> {
+    /// Те же данные, начиная с сэмпла `offset`
+    pub fn offset(&mut self, offset: usize) -> DataMut<'_> {
+        match self {
+            DataMut::Audio(floats) => DataMut::Audio(&mut floats[offset..]),
+            DataMut::Float(float) => DataMut::Float(*float),
+        }
+    }
+}
+
 impl<'a> From<&'a mut [f32]> for DataMut<'a> {
     fn from(audio: &'a mut [f32]) -> Self {
         Self::Audio(audio)
@@ -0,0 +1,40 @@
+use crate::{DataMut, DataRef, Node, Ports, MAX_BLOCK_SIZE};
+
+/// Object-safe версия [`Node`], входы и выходы адресуются по именам сокетов
+pub trait DynNode: Send {
+    fn process_block(&mut self, n: usize, ports: &mut Ports);
+}
+
+/// Заполнение структуры параметров узла по именам сокетов
+pub trait DynParameters<'a>: Default {
+    fn set_input(&mut self, name: &str, data: DataRef<'a>);
+    fn set_output(&mut self, name: &str, data: DataMut<'a>);
+}
+
+impl<T> DynNode for T
+where
+    T: for<'a> Node<'a> + Send,
+    for<'a> <T as Node<'a>>::Parameters: DynParameters<'a>,
+{
+    fn process_block(&mut self, n: usize, ports: &mut Ports) {
+        assert!(
+            n <= MAX_BLOCK_SIZE,
+            "Block size {n} exceeds {MAX_BLOCK_SIZE}"
+        );
+
+        let (inputs, outputs) = ports.split_mut();
+        let mut parameters = <T as Node>::Parameters::default();
+
+        for input in inputs {
+            if let Some(data) = input.data(n) {
+                parameters.set_input(input.name(), data);
+            }
+        }
+
+        for output in outputs {
+            parameters.set_output(output.name(), output.data_mut(n));
+        }
+
+        Node::process::<MAX_BLOCK_SIZE>(self, n, parameters);
+    }
+}
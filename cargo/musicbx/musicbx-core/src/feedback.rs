@@ -0,0 +1,26 @@
+use crate::{DataRef, MAX_BLOCK_SIZE};
+
+/// Состояние задержанного маршрута в составном узле,
+/// хранит выход узла-источника до следующего блока или сэмпла
+#[derive(Debug, Clone)]
+pub struct Feedback {
+    buffer: [f32; MAX_BLOCK_SIZE],
+}
+
+impl Default for Feedback {
+    fn default() -> Self {
+        Feedback {
+            buffer: [0.0; MAX_BLOCK_SIZE],
+        }
+    }
+}
+
+impl Feedback {
+    pub fn data(&self, offset: usize) -> DataRef<'_> {
+        DataRef::Audio(&self.buffer[offset..])
+    }
+
+    pub fn store(&mut self, samples: &[f32]) {
+        self.buffer[..samples.len()].copy_from_slice(samples);
+    }
+}
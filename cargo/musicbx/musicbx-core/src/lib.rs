@@ -1,9 +1,24 @@
 // #![feature(generic_associated_types)]
 
+pub use buffer::AudioBuffer;
 pub use data::{DataMut, DataRef};
+pub use dyn_node::{DynNode, DynParameters};
+pub use feedback::Feedback;
 pub use node::Node;
+pub use ports::{InputPort, OutputPort, Ports};
 pub use sample_rate::FromSampleRate;
 
+// для кода, который генерируют макросы из musicbx-derive
+#[doc(hidden)]
+pub use musicbx_types as types;
+
+mod buffer;
 mod data;
+mod dyn_node;
+mod feedback;
 mod node;
+mod ports;
 mod sample_rate;
+
+/// Максимальное количество сэмплов, которое можно обработать за один вызов
+pub const MAX_BLOCK_SIZE: usize = 1024;
@@ -0,0 +1,139 @@
+use crate::{DataMut, DataRef, MAX_BLOCK_SIZE};
+
+#[derive(Debug, Default, Clone)]
+pub struct Ports {
+    inputs: Vec<InputPort>,
+    outputs: Vec<OutputPort>,
+}
+
+#[derive(Debug, Clone)]
+pub struct InputPort {
+    name: &'static str,
+    value: InputValue,
+    // буфер выделяется заранее, чтобы подключение кабеля не требовало аллокаций
+    buffer: Vec<f32>,
+}
+
+#[derive(Debug, Clone)]
+enum InputValue {
+    Default,
+    Float(f32),
+    Audio,
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputPort {
+    name: &'static str,
+    buffer: Vec<f32>,
+}
+
+impl Ports {
+    /// Порты с именами сокетов, входы без кабелей берут значения по умолчанию
+    pub fn new(
+        inputs: impl IntoIterator<Item = &'static str>,
+        outputs: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        let mut result = Ports::default();
+
+        for name in inputs {
+            if result.input_index(name).is_none() {
+                result.inputs.push(InputPort::new(name));
+            }
+        }
+
+        for name in outputs {
+            if result.output_index(name).is_none() {
+                result.outputs.push(OutputPort {
+                    name,
+                    buffer: vec![0.0; MAX_BLOCK_SIZE],
+                });
+            }
+        }
+
+        result
+    }
+
+    pub fn inputs(&self) -> &[InputPort] {
+        &self.inputs
+    }
+
+    pub fn outputs(&self) -> &[OutputPort] {
+        &self.outputs
+    }
+
+    pub fn split_mut(&mut self) -> (&[InputPort], &mut [OutputPort]) {
+        (&self.inputs, &mut self.outputs)
+    }
+
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        self.inputs.iter().position(|x| x.name == name)
+    }
+
+    pub fn output_index(&self, name: &str) -> Option<usize> {
+        self.outputs.iter().position(|x| x.name == name)
+    }
+
+    pub fn input_mut(&mut self, index: usize) -> &mut InputPort {
+        &mut self.inputs[index]
+    }
+}
+
+impl InputPort {
+    fn new(name: &'static str) -> Self {
+        InputPort {
+            name,
+            value: InputValue::Default,
+            buffer: vec![0.0; MAX_BLOCK_SIZE],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Возвращает `None`, если у входа нет ни кабеля, ни значения параметра
+    pub fn data(&self, n: usize) -> Option<DataRef<'_>> {
+        match &self.value {
+            InputValue::Default => None,
+            InputValue::Float(value) => Some(DataRef::Float(*value)),
+            InputValue::Audio => Some(DataRef::Audio(&self.buffer[..n])),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.value = InputValue::Default;
+    }
+
+    pub fn set_float(&mut self, value: f32) {
+        self.value = InputValue::Float(value);
+    }
+
+    pub fn set_audio(&mut self) {
+        self.value = InputValue::Audio;
+    }
+
+    pub fn is_audio(&self) -> bool {
+        matches!(self.value, InputValue::Audio)
+    }
+
+    pub fn audio_mut(&mut self) -> Option<&mut [f32]> {
+        match self.value {
+            InputValue::Audio => Some(&mut self.buffer),
+            _ => None,
+        }
+    }
+}
+
+impl OutputPort {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn buffer(&self) -> &[f32] {
+        &self.buffer
+    }
+
+    pub fn data_mut(&mut self, n: usize) -> DataMut<'_> {
+        DataMut::Audio(&mut self.buffer[..n])
+    }
+}
@@ -0,0 +1,90 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Data, DeriveInput, Lifetime, Type};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DeriveError {
+    #[error("DynParameters can be derived only for structs")]
+    NotStruct,
+
+    #[error("DynParameters can't be derived for tuple structs")]
+    UnexpectedTupleStruct,
+
+    #[error("DynParameters can be derived only for structs with a lifetime parameter")]
+    NoLifetime,
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream, DeriveError> {
+    let fields = match &input.data {
+        Data::Struct(struct_data) => &struct_data.fields,
+        _ => Err(DeriveError::NotStruct)?,
+    };
+
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+    for field in fields {
+        let ident = field
+            .ident
+            .as_ref()
+            .ok_or(DeriveError::UnexpectedTupleStruct)?;
+
+        match data_type_name(&field.ty).as_deref() {
+            Some("DataRef") => inputs.push(ident.clone()),
+            Some("DataMut") => outputs.push(ident.clone()),
+            _ => (),
+        }
+    }
+
+    let lifetime = input
+        .generics
+        .lifetimes()
+        .next()
+        .map(|x| &x.lifetime)
+        .ok_or(DeriveError::NoLifetime)?;
+
+    Ok(dyn_parameters_impl(
+        &input.ident,
+        lifetime,
+        &inputs,
+        &outputs,
+    ))
+}
+
+pub fn dyn_parameters_impl(
+    name: &Ident,
+    lifetime: &Lifetime,
+    inputs: &[Ident],
+    outputs: &[Ident],
+) -> TokenStream {
+    let input_names = inputs.iter().map(ToString::to_string);
+    let output_names = outputs.iter().map(ToString::to_string);
+
+    quote! {
+        #[automatically_derived]
+        impl<#lifetime> musicbx::DynParameters<#lifetime> for #name<#lifetime> {
+            #[inline]
+            fn set_input(&mut self, name: &str, data: musicbx::DataRef<#lifetime>) {
+                match name {
+                    #( #input_names => self.#inputs = data, )*
+                    _ => (),
+                }
+            }
+
+            #[inline]
+            fn set_output(&mut self, name: &str, data: musicbx::DataMut<#lifetime>) {
+                match name {
+                    #( #output_names => self.#outputs = data, )*
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+fn data_type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|x| x.ident.to_string()),
+        _ => None,
+    }
+}
@@ -2,8 +2,10 @@ use syn::{parse_macro_input, DeriveInput};
 
 use node::Routing;
 
+mod dyn_parameters;
 mod from_sample_rate;
 mod node;
+mod node_parameters;
 
 #[proc_macro_attribute]
 pub fn node(
@@ -20,3 +22,17 @@ pub fn derive_from_sample_rate(input: proc_macro::TokenStream) -> proc_macro::To
     let input = parse_macro_input!(input as DeriveInput);
     from_sample_rate::derive(input).unwrap().into()
 }
+
+#[proc_macro_derive(DynParameters)]
+pub fn derive_dyn_parameters(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    dyn_parameters::derive(input).unwrap().into()
+}
+
+#[proc_macro_derive(NodeParameters, attributes(node_parameters, input, parameter, output))]
+pub fn derive_node_parameters(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    node_parameters::derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -8,36 +8,195 @@ use petgraph::Graph;
 use proc_macro2::Span;
 use quote::format_ident;
 use quote::quote;
-use syn::parse::{Parse, ParseStream};
+use syn::parse::{Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
-use syn::{Data, DataStruct, DeriveInput, Ident, Token};
+use syn::token::Paren;
+use syn::{
+    parenthesized, Data, DataStruct, DeriveInput, Field, Fields, Ident, Lifetime, Lit, LitStr,
+    Token,
+};
 use thiserror::Error;
 
+use crate::dyn_parameters::dyn_parameters_impl;
+use crate::node_parameters::{ParameterOptions, SocketSignal};
+
 #[derive(Clone)]
 pub struct Routing {
-    routes: Punctuated<Route, Token![,]>,
+    uid: Option<LitStr>,
+    routes: Vec<Route>,
+    parameters: Vec<ParameterDeclaration>,
 }
 
 impl Parse for Routing {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let items: Punctuated<RoutingItem, Token![,]> =
+            input.parse_terminated(RoutingItem::parse)?;
+
+        let mut uid = None;
+        let mut routes = Vec::new();
+        let mut parameters = Vec::new();
+        for item in items {
+            match item {
+                RoutingItem::Uid(x) => uid = Some(x),
+                RoutingItem::Route(route) => routes.push(route),
+                RoutingItem::Parameter(parameter) => parameters.push(parameter),
+            }
+        }
+
         Ok(Routing {
-            routes: input.parse_terminated(Route::parse)?,
+            uid,
+            routes,
+            parameters,
+        })
+    }
+}
+
+enum RoutingItem {
+    Uid(LitStr),
+    Route(Route),
+    Parameter(ParameterDeclaration),
+}
+
+impl Parse for RoutingItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `uid = "FastTremolo"` заменяет uid по умолчанию, так делает кодогенерация
+        if input.peek(kw::uid) && input.peek2(Token![=]) {
+            input.parse::<kw::uid>()?;
+            input.parse::<Token![=]>()?;
+            Ok(RoutingItem::Uid(input.parse()?))
+        } else if input.peek2(Token![:]) {
+            Ok(RoutingItem::Parameter(input.parse()?))
+        } else {
+            Ok(RoutingItem::Route(input.parse()?))
+        }
+    }
+}
+
+// `rate: HzSlow = 4.0` объявляет входной параметр составного узла,
+// а `rate: HzSlow(max = 20.0) = 4.0` ещё и меняет его диапазон
+// или задаёт варианты: `wave: Choice(choices = ["Sine", "Saw"]) = 0`
+#[derive(Debug, Clone)]
+struct ParameterDeclaration {
+    name: Ident,
+    kind: Ident,
+    options: ParameterOptions,
+    default: f32,
+}
+
+impl Parse for ParameterDeclaration {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let kind = input.parse()?;
+        let options = if input.peek(Paren) {
+            input.parse()?
+        } else {
+            ParameterOptions::default()
+        };
+        input.parse::<Token![=]>()?;
+
+        let default = parse_number(input)?;
+
+        Ok(ParameterDeclaration {
+            name,
+            kind,
+            options,
+            default,
         })
     }
 }
 
+fn parse_number(input: ParseStream) -> syn::Result<f32> {
+    let negative = input.parse::<Option<Token![-]>>()?.is_some();
+    let value: f32 = match input.parse()? {
+        Lit::Float(x) => x.base10_parse()?,
+        Lit::Int(x) => x.base10_parse()?,
+        lit => return Err(syn::Error::new_spanned(lit, "Expected a number")),
+    };
+
+    Ok(if negative { -value } else { value })
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 struct Route {
     from: RouteEnd,
     to: RouteEnd,
+    delay: Option<Delay>,
 }
 
 impl Parse for Route {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let from = input.parse()?;
-        input.parse::<Token![->]>()?;
-        let to = input.parse()?;
-        Ok(Route { from, to })
+        let arrow = input.parse::<Token![->]>()?;
+
+        // `a.output -> delay(block) b.input` разрывает цикл в графе, а `delay(sample)`
+        // заставляет все вложенные узлы обрабатывать по одному сэмплу за вызов
+        let delay = if input.peek(kw::delay) && input.peek2(Paren) {
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        let to: RouteEnd = input.parse()?;
+        if let RouteEnd::Const(_) = to {
+            return Err(syn::Error::new_spanned(
+                arrow,
+                "A constant can't be a route target",
+            ));
+        }
+
+        let inner = matches!((&from, &to), (RouteEnd::Inner(..), RouteEnd::Inner(..)));
+        if delay.is_some() && !inner {
+            return Err(syn::Error::new_spanned(
+                arrow,
+                "Only routes between inner nodes can be delayed",
+            ));
+        }
+
+        Ok(Route { from, to, delay })
+    }
+}
+
+impl Route {
+    fn feedback_ident(&self) -> Ident {
+        match (&self.from, &self.to) {
+            (RouteEnd::Inner(a, b), RouteEnd::Inner(c, d)) => {
+                format_ident!("__feedback_{a}_{b}_{c}_{d}")
+            }
+            _ => unreachable!("only inner routes can be delayed"),
+        }
+    }
+}
+
+mod kw {
+    syn::custom_keyword!(delay);
+    syn::custom_keyword!(block);
+    syn::custom_keyword!(sample);
+    syn::custom_keyword!(uid);
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Delay {
+    Block,
+    Sample,
+}
+
+impl Parse for Delay {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        input.parse::<kw::delay>()?;
+        let content;
+        parenthesized!(content in input);
+
+        let lookahead = content.lookahead1();
+        if lookahead.peek(kw::block) {
+            content.parse::<kw::block>()?;
+            Ok(Delay::Block)
+        } else if lookahead.peek(kw::sample) {
+            content.parse::<kw::sample>()?;
+            Ok(Delay::Sample)
+        } else {
+            Err(lookahead.error())
+        }
     }
 }
 
@@ -45,10 +204,16 @@ impl Parse for Route {
 enum RouteEnd {
     Param(Ident),
     Inner(Ident, Ident),
+    // `0.5 -> a.input` подаёт на вход постоянное значение, f32 хранится в битах
+    Const(u32),
 }
 
 impl Parse for RouteEnd {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Token![-]) || input.peek(Lit) {
+            return Ok(RouteEnd::Const(parse_number(input)?.to_bits()));
+        }
+
         let ident: Ident = input.parse()?;
         let dot: Option<Token![.]> = input.parse()?;
 
@@ -64,38 +229,147 @@ impl Parse for RouteEnd {
 
 #[derive(Error, Debug)]
 enum CompositeNodeError {
-    #[error("A cyclic graph is not supported")]
+    #[error("A cyclic graph is not supported, use `-> delay(block)` or `-> delay(sample)` to break the cycle")]
     CyclicGraph,
 }
 
-pub fn node_attribute_macro(input: DeriveInput, routing: Routing) -> proc_macro2::TokenStream {
+pub fn node_attribute_macro(mut input: DeriveInput, routing: Routing) -> proc_macro2::TokenStream {
     let input_struct = match &input.data {
-        Data::Struct(x) => x,
+        Data::Struct(x) => x.clone(),
         _ => panic!("Expected struct"),
     };
 
     let parameter_struct_definition = define_parameters_struct(&input, &routing);
-    let impls = define_impls(&input, input_struct, &routing);
+    let impls = define_impls(&input, &input_struct, &routing)
+        .unwrap_or_else(|err| syn::Error::new_spanned(&input.ident, err).to_compile_error());
+    let definition = define_definition(&input, &routing);
+    add_feedback_fields(&mut input, &routing);
 
     quote! {
         #input
         #parameter_struct_definition
         #impls
+        #definition
     }
 }
 
-fn define_parameters_struct(input: &DeriveInput, routing: &Routing) -> proc_macro2::TokenStream {
+// uid составного узла по умолчанию совпадает с путём до его структуры
+fn define_definition(input: &DeriveInput, routing: &Routing) -> proc_macro2::TokenStream {
     let vis = &input.vis;
     let ident = &input.ident;
 
-    let parameters_ident = format_ident!("{}Parameters", ident);
-    let inputs: Vec<_> = routing
+    // входы и выходы составного узла передают звук
+    let signal = SocketSignal::default().definition_fields();
+
+    // объявленные параметры не повторяются среди входов
+    let inputs = routing
         .routes
         .iter()
         .filter_map(|route| match &route.from {
             RouteEnd::Param(param) => Some(param),
             _ => None,
         })
+        .filter(|param| routing.parameters.iter().all(|x| &x.name != *param))
+        .map(ToString::to_string)
+        .unique()
+        .enumerate()
+        .map(|(number, name)| {
+            quote! {
+                musicbx::types::NodeInput {
+                    number: #number,
+                    name: #name,
+                    #signal
+                }
+            }
+        });
+
+    let outputs = routing
+        .routes
+        .iter()
+        .filter_map(|route| match &route.to {
+            RouteEnd::Param(param) => Some(param.to_string()),
+            _ => None,
+        })
+        .unique()
+        .enumerate()
+        .map(|(number, name)| {
+            quote! {
+                musicbx::types::NodeOutput {
+                    number: #number,
+                    name: #name,
+                    #signal
+                }
+            }
+        });
+
+    let parameters = routing
+        .parameters
+        .iter()
+        .unique_by(|x| &x.name)
+        .enumerate()
+        .map(|(number, parameter)| {
+            let ParameterDeclaration {
+                name,
+                kind,
+                options,
+                default,
+            } = parameter;
+            let name = name.to_string();
+            let default = format!("{default:?}");
+            let options = options.definition_fields(kind);
+
+            quote! {
+                musicbx::types::NodeParameter {
+                    number: #number,
+                    kind: musicbx::types::NodeParameterKind::#kind,
+                    #options
+                    default: #default,
+                    name: #name,
+                }
+            }
+        });
+
+    let uid = match &routing.uid {
+        Some(uid) => quote! { #uid },
+        None => quote! { concat!(module_path!(), "::", stringify!(#ident)) },
+    };
+
+    quote! {
+        impl #ident {
+            #vis const fn definition() -> musicbx::types::NodeDefinition {
+                musicbx::types::NodeDefinition {
+                    uid: #uid,
+                    inputs: {
+                        const INPUTS: &[musicbx::types::NodeInput] = &[ #( #inputs, )* ];
+                        INPUTS
+                    },
+                    outputs: {
+                        const OUTPUTS: &[musicbx::types::NodeOutput] = &[ #( #outputs, )* ];
+                        OUTPUTS
+                    },
+                    parameters: {
+                        const PARAMETERS: &[musicbx::types::NodeParameter] = &[ #( #parameters, )* ];
+                        PARAMETERS
+                    },
+                }
+            }
+        }
+    }
+}
+
+fn define_parameters_struct(input: &DeriveInput, routing: &Routing) -> proc_macro2::TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+
+    let parameters_ident = format_ident!("{}Parameters", ident);
+    let inputs: Vec<_> = routing
+        .parameters
+        .iter()
+        .map(|x| &x.name)
+        .chain(routing.routes.iter().filter_map(|route| match &route.from {
+            RouteEnd::Param(param) => Some(param),
+            _ => None,
+        }))
         .unique()
         .collect();
 
@@ -109,12 +383,40 @@ fn define_parameters_struct(input: &DeriveInput, routing: &Routing) -> proc_macr
         .unique()
         .collect();
 
+    let dyn_parameters_impl = dyn_parameters_impl(
+        &parameters_ident,
+        &Lifetime::new("'a", Span::call_site()),
+        &inputs.iter().copied().cloned().collect_vec(),
+        &outputs.iter().copied().cloned().collect_vec(),
+    );
+
+    let input_defaults =
+        inputs.iter().map(
+            |input| match routing.parameters.iter().find(|x| &&x.name == input) {
+                Some(ParameterDeclaration { default, .. }) => {
+                    quote! { musicbx::DataRef::Float(#default) }
+                }
+                None => quote! { musicbx::DataRef::default() },
+            },
+        );
+
     quote! {
-        #[derive(Default)]
         #vis struct #parameters_ident<'a> {
             #(#vis #inputs: musicbx::DataRef<'a>, )*
             #(#vis #outputs: musicbx::DataMut<'a>, )*
         }
+
+        impl<'a> Default for #parameters_ident<'a> {
+            #[inline]
+            fn default() -> Self {
+                Self {
+                    #( #inputs: #input_defaults, )*
+                    #( #outputs: musicbx::DataMut::default(), )*
+                }
+            }
+        }
+
+        #dyn_parameters_impl
     }
 }
 
@@ -122,27 +424,62 @@ fn define_impls(
     input: &DeriveInput,
     input_struct: &DataStruct,
     routing: &Routing,
-) -> proc_macro2::TokenStream {
+) -> Result<proc_macro2::TokenStream, CompositeNodeError> {
     let ident = &input.ident;
-    let sorted_fields = fields_topo_sort(input_struct, routing).unwrap();
+    let sorted_fields = fields_topo_sort(input_struct, routing)?;
+
+    // одинаковые маршруты не должны удваивать сигнал
+    let routes: Vec<&Route> = routing.routes.iter().unique().collect();
 
+    let mut temps = Vec::new();
     let mut parts = Vec::new();
+    let mut output_writes = Vec::new();
+    let mut sample_stores = Vec::new();
+    let mut block_stores = Vec::new();
 
+    // выход узла пишется прямо в выход составного узла, если больше никуда не идёт
     let mut all_outputs = HashMap::new();
-    for route in &routing.routes {
-        if let RouteEnd::Param(x) = &route.from {
-            all_outputs.insert(&route.from, x.clone());
-        }
-
+    for route in &routes {
         if let RouteEnd::Inner(subnode, field) = &route.from {
-            let out_name = Ident::new(&format!("__{subnode}_{field}"), Span::call_site());
-            parts.push(quote! {
-                let mut #out_name = [0.0f32; N];
-            });
-            all_outputs.insert(&route.from, out_name.clone());
+            let outgoing: Vec<_> = routes.iter().filter(|x| x.from == route.from).collect();
+            let direct = match outgoing[..] {
+                [Route {
+                    to: to @ RouteEnd::Param(_),
+                    ..
+                }] => routes.iter().filter(|x| &x.to == to).count() == 1,
+                _ => false,
+            };
+
+            if !direct && !all_outputs.contains_key(&route.from) {
+                let out_name = Ident::new(&format!("__{subnode}_{field}"), Span::call_site());
+                temps.push(quote! {
+                    let mut #out_name = [0.0f32; N];
+                });
+                all_outputs.insert(&route.from, out_name);
+            }
         }
     }
 
+    let source_data = |route: &Route| match (&route.from, route.delay) {
+        (RouteEnd::Param(param), _) => quote! { #param.offset(__offset) },
+        (RouteEnd::Const(bits), _) => {
+            let value = f32::from_bits(*bits);
+            quote! { musicbx::DataRef::Float(#value) }
+        }
+        (inner @ RouteEnd::Inner(..), None) => {
+            let temp = &all_outputs[inner];
+            quote! { musicbx::DataRef::from(&#temp[__offset..]) }
+        }
+        (RouteEnd::Inner(..), Some(Delay::Block)) => {
+            let feedback = route.feedback_ident();
+            quote! { self.#feedback.data(__offset) }
+        }
+        (RouteEnd::Inner(..), Some(Delay::Sample)) => {
+            let feedback = route.feedback_ident();
+            quote! { self.#feedback.data(0) }
+        }
+    };
+
     for field in sorted_fields {
         let field_type = input_struct
             .fields
@@ -151,48 +488,63 @@ fn define_impls(
             .map(|x| &x.ty)
             .expect("expected struct field");
 
-        let inputs: Vec<_> = routing
-            .routes
-            .iter()
-            .filter_map(|route| match &route.to {
-                RouteEnd::Inner(x, y) if x == field => {
-                    let input = match &route.from {
-                        RouteEnd::Param(param) => quote! { #param },
-                        inner @ RouteEnd::Inner(..) => {
-                            let temp = &all_outputs[inner];
-                            quote! { musicbx::DataRef::from(&#temp[..]) }
-                        }
-                    };
-                    Some(quote! { #y: #input })
-                }
-                _ => None,
-            })
-            .collect();
+        // несколько маршрутов в один вход суммируются в отдельный буфер
+        let mut inputs = Vec::new();
+        let mut mixes = Vec::new();
+        for to in routes.iter().map(|x| &x.to).unique() {
+            let y = match to {
+                RouteEnd::Inner(x, y) if x == field => y,
+                _ => continue,
+            };
+
+            let sources: Vec<_> = routes
+                .iter()
+                .filter(|x| &x.to == to)
+                .map(|x| source_data(x))
+                .collect();
+
+            if let [source] = &sources[..] {
+                inputs.push(quote! { #y: #source });
+            } else {
+                let sum = format_ident!("__sum_{field}_{y}");
+                temps.push(quote! {
+                    let mut #sum = [0.0f32; N];
+                });
+                mixes.push(quote! {
+                    let __sources = [ #( #sources, )* ];
+                    for __i in 0..__n {
+                        #sum[__offset + __i] = __sources.iter().map(|x| x[__i]).sum();
+                    }
+                });
+                inputs.push(quote! { #y: musicbx::DataRef::from(&#sum[__offset..]) });
+            }
+        }
 
-        let outputs: Vec<_> = routing
-            .routes
-            .iter()
-            .filter_map(|route| match &route.from {
-                RouteEnd::Inner(x, y) if x == field => {
-                    let output = match &route.to {
-                        RouteEnd::Param(param) => quote! { #param },
-                        RouteEnd::Inner(..) => {
-                            let temp = &all_outputs[&route.from];
-                            quote! { musicbx::DataMut::from(&mut #temp[..]) }
-                        }
-                    };
-                    Some(quote! { #y: #output })
+        let mut outputs = Vec::new();
+        for route in routes.iter().unique_by(|x| &x.from) {
+            let y = match &route.from {
+                RouteEnd::Inner(x, y) if x == field => y,
+                _ => continue,
+            };
+
+            match (all_outputs.get(&route.from), &route.to) {
+                (Some(temp), _) => {
+                    outputs.push(quote! { #y: musicbx::DataMut::from(&mut #temp[__offset..]) });
+                }
+                (None, RouteEnd::Param(param)) => {
+                    outputs.push(quote! { #y: #param.offset(__offset) });
                 }
-                _ => None,
-            })
-            .collect();
+                (None, _) => unreachable!("inner routes always use a buffer"),
+            }
+        }
 
         parts.push(quote! {
             {
+                #( { #mixes } )*
                 type _Parameters<'a> = <#field_type as musicbx::Node<'a>>::Parameters;
                 <#field_type as musicbx::Node>::process::<N>(
                     &mut self.#field,
-                    n,
+                    __n,
                     _Parameters {
                         #( #inputs, )*
                         #( #outputs, )*
@@ -202,6 +554,60 @@ fn define_impls(
         });
     }
 
+    // выходы, в которые не пишут напрямую, заполняются после обработки всех узлов
+    for to in routes.iter().map(|x| &x.to).unique() {
+        let param = match to {
+            RouteEnd::Param(param) => param,
+            _ => continue,
+        };
+
+        let incoming: Vec<_> = routes.iter().filter(|x| &x.to == to).collect();
+        let direct = match incoming[..] {
+            [route] => {
+                matches!(route.from, RouteEnd::Inner(..)) && !all_outputs.contains_key(&route.from)
+            }
+            _ => false,
+        };
+        if direct {
+            continue;
+        }
+
+        let sources: Vec<_> = incoming.iter().map(|x| source_data(x)).collect();
+        output_writes.push(quote! {
+            let __sources = [ #( #sources, )* ];
+            let mut __output = #param.offset(__offset);
+            for __i in 0..__n {
+                __output[__i] = __sources.iter().map(|x| x[__i]).sum();
+            }
+        });
+    }
+
+    for route in routing.routes.iter().filter(|x| x.delay.is_some()).unique() {
+        let feedback = route.feedback_ident();
+        let temp = &all_outputs[&route.from];
+        match route.delay {
+            Some(Delay::Block) => {
+                block_stores.push(quote! { self.#feedback.store(&#temp[..n]); });
+            }
+            Some(Delay::Sample) => {
+                sample_stores
+                    .push(quote! { self.#feedback.store(&#temp[__offset..__offset + __n]); });
+            }
+            None => (),
+        }
+    }
+
+    // задержка на сэмпл требует обрабатывать все вложенные узлы по одному сэмплу
+    let step = if routing
+        .routes
+        .iter()
+        .any(|x| x.delay == Some(Delay::Sample))
+    {
+        quote! { 1 }
+    } else {
+        quote! { n }
+    };
+
     let mut inputs = Vec::new();
     let mut outputs = Vec::new();
     for route in &routing.routes {
@@ -218,21 +624,49 @@ fn define_impls(
 
     let parameters_ident = format_ident!("{}Parameters", input.ident);
 
-    quote! {
+    Ok(quote! {
         #[automatically_derived]
         #[allow(needless_update)]
         impl<'a> musicbx::Node<'a> for #ident {
             type Parameters = #parameters_ident<'a>;
 
+            #[allow(unused_mut, unused_variables)]
             fn process<const N: usize>(
                 &mut self,
                 n: usize,
                 parameters: Self::Parameters,
             ) {
                 let #parameters_ident { #( #inputs, )* #( mut #outputs, )* .. } = parameters;
-                #( #parts )*
+                #( #temps )*
+
+                let __n = #step;
+                let mut __offset = 0;
+                while __offset < n {
+                    #( #parts )*
+                    #( { #output_writes } )*
+                    #( #sample_stores )*
+                    __offset += __n;
+                }
+
+                #( #block_stores )*
             }
         }
+    })
+}
+
+fn add_feedback_fields(input: &mut DeriveInput, routing: &Routing) {
+    if let Data::Struct(DataStruct {
+        fields: Fields::Named(fields),
+        ..
+    }) = &mut input.data
+    {
+        for route in routing.routes.iter().filter(|x| x.delay.is_some()).unique() {
+            let ident = route.feedback_ident();
+            let field = Field::parse_named
+                .parse2(quote! { #ident: musicbx::Feedback })
+                .expect("valid field");
+            fields.named.push(field);
+        }
     }
 }
 
@@ -254,20 +688,20 @@ fn fields_topo_sort<'a>(
     for node in fields
         .iter()
         .map(Deref::deref)
-        .chain([&input_ident, &output_ident].into_iter())
+        .chain([&input_ident, &output_ident])
     {
         node_indices.insert(node, graph.add_node(node));
     }
 
-    for route in &routing.routes {
+    for route in routing.routes.iter().filter(|x| x.delay.is_none()) {
         let from = match &route.from {
-            RouteEnd::Param(_) => &input_ident,
+            RouteEnd::Param(_) | RouteEnd::Const(_) => &input_ident,
             RouteEnd::Inner(field, _) => field,
         };
         let from = node_indices[from];
 
         let to = match &route.to {
-            RouteEnd::Param(_) => &output_ident,
+            RouteEnd::Param(_) | RouteEnd::Const(_) => &output_ident,
             RouteEnd::Inner(field, _) => field,
         };
         let to = node_indices[to];
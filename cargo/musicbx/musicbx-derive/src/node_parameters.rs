@@ -0,0 +1,461 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{
+    parenthesized, Attribute, Data, DeriveInput, Expr, ExprLit, ExprUnary, Lit, LitStr, Token, UnOp,
+};
+use thiserror::Error;
+
+use crate::dyn_parameters::dyn_parameters_impl;
+
+#[derive(Debug, Error)]
+pub enum DeriveError {
+    #[error("NodeParameters can be derived only for structs")]
+    NotStruct,
+
+    #[error("NodeParameters can't be derived for tuple structs")]
+    UnexpectedTupleStruct,
+
+    #[error("NodeParameters can be derived only for structs with a lifetime parameter")]
+    NoLifetime,
+
+    #[error("Expected a struct named `<Node>Parameters`, got {0}")]
+    UnexpectedName(String),
+
+    #[error("Field {0} must be marked as #[input], #[parameter] or #[output]")]
+    UnknownPortKind(String),
+
+    #[error("Unknown argument {0}")]
+    UnknownArgument(String),
+
+    #[error("Parameter {0} must have a kind")]
+    NoParameterKind(String),
+
+    #[error("Expected a number, got {0}")]
+    NotNumber(String),
+
+    #[error("Expected an array of string literals, got {0}")]
+    NotStrings(String),
+
+    #[error(transparent)]
+    ParseError(#[from] syn::Error),
+}
+
+// ошибка показывается компилятором у того места в атрибутах, где она допущена
+fn spanned(tokens: impl ToTokens, err: DeriveError) -> syn::Error {
+    match err {
+        DeriveError::ParseError(err) => err,
+        err => syn::Error::new_spanned(tokens, err),
+    }
+}
+
+enum Port {
+    Input {
+        default: f32,
+        signal: SocketSignal,
+    },
+    Parameter {
+        kind: Ident,
+        default: f32,
+        options: ParameterOptions,
+    },
+    Output {
+        signal: SocketSignal,
+    },
+}
+
+// `signal = Control, min = 0.0, max = 4.0` у входов и выходов, по умолчанию звук
+// с диапазоном, который задан для типа сигнала
+#[derive(Debug, Clone, Default)]
+pub struct SocketSignal {
+    pub signal: Option<Ident>,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl SocketSignal {
+    fn apply(&mut self, name: &str, value: &Expr) -> Result<bool, DeriveError> {
+        match name {
+            "signal" => self.signal = Some(parse_ident(value)?),
+            "min" => self.min = Some(parse_number(value)?),
+            "max" => self.max = Some(parse_number(value)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub fn definition_fields(&self) -> TokenStream {
+        let signal = match &self.signal {
+            Some(signal) => quote! { musicbx::types::SignalType::#signal },
+            None => quote! { musicbx::types::SignalType::Audio },
+        };
+        let min = match self.min {
+            Some(min) => quote! { #min },
+            None => quote! { #signal.min() },
+        };
+        let max = match self.max {
+            Some(max) => quote! { #max },
+            None => quote! { #signal.max() },
+        };
+
+        quote! {
+            signal: #signal,
+            min: #min,
+            max: #max,
+        }
+    }
+}
+
+// границы и кривая, не указанные явно, берутся из вида параметра,
+// а у выбора из `choices` по умолчанию столько значений, сколько вариантов
+#[derive(Debug, Clone, Default)]
+pub struct ParameterOptions {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub curve: Option<TokenStream>,
+    pub choices: Vec<String>,
+}
+
+// `(min = 0.1, max = 20.0, curve = Exponential)` в объявлениях параметров составных узлов
+impl Parse for ParameterOptions {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        parenthesized!(content in input);
+
+        let mut options = ParameterOptions::default();
+        for Argument { name, value } in content.parse::<Arguments>()?.0 {
+            let known = options
+                .apply(&name.to_string(), &value)
+                .map_err(|err| syn::Error::new_spanned(&value, err))?;
+            if !known {
+                let err = DeriveError::UnknownArgument(name.to_string());
+                return Err(syn::Error::new_spanned(name, err));
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+impl ParameterOptions {
+    // false, если аргумент не относится к диапазону параметра
+    fn apply(&mut self, name: &str, value: &Expr) -> Result<bool, DeriveError> {
+        match name {
+            "min" => self.min = Some(parse_number(value)?),
+            "max" => self.max = Some(parse_number(value)?),
+            "curve" => self.curve = Some(quote! { #value }),
+            "choices" => self.choices = parse_strings(value)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    pub fn definition_fields(&self, kind: &Ident) -> TokenStream {
+        let kind = quote! { musicbx::types::NodeParameterKind::#kind };
+        let min = match self.min {
+            Some(min) => quote! { #min },
+            None => quote! { #kind.min() },
+        };
+        let max = match self.max {
+            Some(max) => quote! { #max },
+            None if !self.choices.is_empty() => {
+                let max = (self.choices.len() - 1) as f32;
+                quote! { #max }
+            }
+            None => quote! { #kind.max() },
+        };
+        let curve = match &self.curve {
+            Some(curve) => quote! { musicbx::types::ParameterCurve::#curve },
+            None => quote! { #kind.curve(#min, #max) },
+        };
+        let choices = &self.choices;
+
+        quote! {
+            min: #min,
+            max: #max,
+            curve: #curve,
+            choices: &[ #( #choices, )* ],
+        }
+    }
+}
+
+// аргументы вида `kind = HzWide, default = 440.0, max = 2000.0, curve = Skewed { skew: 0.5 }`
+struct Arguments(Punctuated<Argument, Token![,]>);
+
+struct Argument {
+    name: Ident,
+    value: Expr,
+}
+
+impl Parse for Arguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(Arguments(input.parse_terminated(Argument::parse)?))
+    }
+}
+
+impl Parse for Argument {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let value = input.parse()?;
+        Ok(Argument { name, value })
+    }
+}
+
+pub fn derive(input: DeriveInput) -> syn::Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(struct_data) => &struct_data.fields,
+        _ => Err(spanned(&input.ident, DeriveError::NotStruct))?,
+    };
+
+    let name = &input.ident;
+    let node = name
+        .to_string()
+        .strip_suffix("Parameters")
+        .filter(|x| !x.is_empty())
+        .map(|x| format_ident!("{x}"))
+        .ok_or_else(|| spanned(name, DeriveError::UnexpectedName(name.to_string())))?;
+
+    let uid = match input
+        .attrs
+        .iter()
+        .find(|x| x.path.is_ident("node_parameters"))
+    {
+        Some(attr) => {
+            let Argument { name, value } = attr.parse_args()?;
+            match (name.to_string().as_str(), value) {
+                (
+                    "uid",
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(uid), ..
+                    }),
+                ) => quote! { #uid },
+                (unknown, _) => {
+                    let err = DeriveError::UnknownArgument(unknown.to_string());
+                    Err(spanned(&name, err))?
+                }
+            }
+        }
+        None => quote! { concat!(module_path!(), "::", stringify!(#node)) },
+    };
+
+    let mut ports = Vec::new();
+    for field in fields {
+        let ident = field
+            .ident
+            .as_ref()
+            .ok_or_else(|| spanned(field, DeriveError::UnexpectedTupleStruct))?;
+        let port = field
+            .attrs
+            .iter()
+            .find_map(|attr| parse_port(ident, attr).transpose())
+            .ok_or_else(|| spanned(ident, DeriveError::UnknownPortKind(ident.to_string())))??;
+        ports.push((ident, port));
+    }
+
+    let mut inputs = Vec::new();
+    let mut parameters = Vec::new();
+    let mut outputs = Vec::new();
+    let mut defaults = Vec::new();
+    for (ident, port) in &ports {
+        let socket_name = LitStr::new(&ident.to_string(), ident.span());
+        match port {
+            Port::Input { default, signal } => {
+                let number = inputs.len();
+                let signal = signal.definition_fields();
+                inputs.push(quote! {
+                    musicbx::types::NodeInput {
+                        number: #number,
+                        name: #socket_name,
+                        #signal
+                    }
+                });
+                defaults.push(quote! { #ident: musicbx::DataRef::Float(#default) });
+            }
+            Port::Parameter {
+                kind,
+                default,
+                options,
+            } => {
+                let number = parameters.len();
+                let default_string = format!("{default:?}");
+                let options = options.definition_fields(kind);
+                parameters.push(quote! {
+                    musicbx::types::NodeParameter {
+                        number: #number,
+                        kind: musicbx::types::NodeParameterKind::#kind,
+                        #options
+                        default: #default_string,
+                        name: #socket_name,
+                    }
+                });
+                defaults.push(quote! { #ident: musicbx::DataRef::Float(#default) });
+            }
+            Port::Output { signal } => {
+                let number = outputs.len();
+                let signal = signal.definition_fields();
+                outputs.push(quote! {
+                    musicbx::types::NodeOutput {
+                        number: #number,
+                        name: #socket_name,
+                        #signal
+                    }
+                });
+                defaults.push(quote! { #ident: musicbx::DataMut::Float(0.0) });
+            }
+        }
+    }
+
+    let lifetime = input
+        .generics
+        .lifetimes()
+        .next()
+        .map(|x| &x.lifetime)
+        .ok_or_else(|| spanned(name, DeriveError::NoLifetime))?;
+
+    // параметры тоже являются входами узла
+    let dyn_inputs: Vec<Ident> = ports
+        .iter()
+        .filter(|(_, port)| !matches!(port, Port::Output { .. }))
+        .map(|(ident, _)| (*ident).clone())
+        .collect();
+    let dyn_outputs: Vec<Ident> = ports
+        .iter()
+        .filter(|(_, port)| matches!(port, Port::Output { .. }))
+        .map(|(ident, _)| (*ident).clone())
+        .collect();
+    let dyn_parameters_impl = dyn_parameters_impl(name, lifetime, &dyn_inputs, &dyn_outputs);
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<#lifetime> Default for #name<#lifetime> {
+            #[inline]
+            fn default() -> Self {
+                Self {
+                    #( #defaults, )*
+                }
+            }
+        }
+
+        #dyn_parameters_impl
+
+        impl #node {
+            pub const fn definition() -> musicbx::types::NodeDefinition {
+                musicbx::types::NodeDefinition {
+                    uid: #uid,
+                    // диапазоны по умолчанию вычисляются const fn, а их результат
+                    // не продлевается до 'static без отдельной константы
+                    inputs: {
+                        const INPUTS: &[musicbx::types::NodeInput] = &[ #( #inputs, )* ];
+                        INPUTS
+                    },
+                    outputs: {
+                        const OUTPUTS: &[musicbx::types::NodeOutput] = &[ #( #outputs, )* ];
+                        OUTPUTS
+                    },
+                    parameters: {
+                        const PARAMETERS: &[musicbx::types::NodeParameter] = &[ #( #parameters, )* ];
+                        PARAMETERS
+                    },
+                }
+            }
+        }
+    })
+}
+
+fn parse_port(field: &Ident, attr: &Attribute) -> syn::Result<Option<Port>> {
+    let kind = match attr.path.get_ident() {
+        Some(x) if x == "input" || x == "parameter" || x == "output" => x.to_string(),
+        _ => return Ok(None),
+    };
+
+    let arguments = if attr.tokens.is_empty() {
+        Punctuated::new()
+    } else {
+        attr.parse_args::<Arguments>()?.0
+    };
+
+    let mut parameter_kind = None;
+    let mut default = None;
+    let mut options = ParameterOptions::default();
+    let mut signal = SocketSignal::default();
+    for Argument { name, value } in arguments {
+        let invalid_value = |err| spanned(&value, err);
+        match (kind.as_str(), name.to_string().as_str()) {
+            ("input" | "parameter", "default") => {
+                default = Some(parse_number(&value).map_err(invalid_value)?)
+            }
+            ("parameter", "kind") => {
+                parameter_kind = Some(parse_ident(&value).map_err(invalid_value)?)
+            }
+            ("parameter", x) if options.apply(x, &value).map_err(invalid_value)? => {}
+            ("input" | "output", x) if signal.apply(x, &value).map_err(invalid_value)? => {}
+            (_, x) => Err(spanned(&name, DeriveError::UnknownArgument(x.to_string())))?,
+        }
+    }
+
+    let port = match kind.as_str() {
+        "input" => Port::Input {
+            default: default.unwrap_or(0.0),
+            signal,
+        },
+        "parameter" => Port::Parameter {
+            kind: parameter_kind
+                .ok_or_else(|| spanned(attr, DeriveError::NoParameterKind(field.to_string())))?,
+            default: default.unwrap_or(0.0),
+            options,
+        },
+        _ => Port::Output { signal },
+    };
+
+    Ok(Some(port))
+}
+
+fn parse_ident(expr: &Expr) -> Result<Ident, DeriveError> {
+    match expr {
+        Expr::Path(path) => path
+            .path
+            .get_ident()
+            .cloned()
+            .ok_or_else(|| DeriveError::UnknownArgument(quote!(#expr).to_string())),
+        _ => Err(DeriveError::UnknownArgument(quote!(#expr).to_string())),
+    }
+}
+
+fn parse_number(expr: &Expr) -> Result<f32, DeriveError> {
+    let not_number = || DeriveError::NotNumber(quote!(#expr).to_string());
+
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Float(x), ..
+        }) => x.base10_parse().map_err(Into::into),
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(x), ..
+        }) => x.base10_parse().map_err(Into::into),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => parse_number(expr).map(|x| -x),
+        _ => Err(not_number()),
+    }
+}
+
+fn parse_strings(expr: &Expr) -> Result<Vec<String>, DeriveError> {
+    let not_strings = || DeriveError::NotStrings(quote!(#expr).to_string());
+
+    match expr {
+        Expr::Array(array) => array
+            .elems
+            .iter()
+            .map(|x| match x {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(x), ..
+                }) => Ok(x.value()),
+                _ => Err(not_strings()),
+            })
+            .collect(),
+        _ => Err(not_strings()),
+    }
+}
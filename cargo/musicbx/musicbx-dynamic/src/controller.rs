@@ -0,0 +1,445 @@
+use std::collections::HashMap;
+
+use petgraph::algo::toposort;
+use petgraph::Graph as PetGraph;
+use rtrb::{Consumer, Producer, RingBuffer};
+
+use musicbx_core::{DynNode, Ports};
+use musicbx_types::patch::{Cable, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+use musicbx_types::{socket_conversion, NodeDefinition, SignalConversion};
+
+use crate::processor::{
+    Command, Garbage, NodeSlot, OutputChannel, OutputRoute, Route, Schedule, Source, Step,
+    GARBAGE_PER_COMMAND,
+};
+use crate::{DynamicModule, GraphError, GraphProcessor};
+
+/// Часть графа, которая хранит его структуру и отправляет изменения в [`GraphProcessor`]
+pub struct GraphController {
+    sr: f32,
+    slots: Vec<Option<SlotInfo>>,
+    synthetic_inputs: Vec<usize>,
+    synthetic_outputs: Vec<usize>,
+    cables: Vec<Cable>,
+    // индексы в slots в порядке обработки
+    order: Vec<usize>,
+    // размер хранилища узлов в GraphProcessor
+    storage_len: usize,
+    capacity: usize,
+    commands: Producer<Command>,
+    garbage: Consumer<Garbage>,
+}
+
+struct SlotInfo {
+    id: usize,
+    definition: NodeDefinition,
+    inputs: Vec<&'static str>,
+    outputs: Vec<&'static str>,
+}
+
+impl GraphController {
+    /// `capacity` ограничивает количество изменений, которые ещё не забрал аудиопоток
+    pub fn new(sr: f32, capacity: usize) -> (GraphController, GraphProcessor) {
+        let (commands_producer, commands_consumer) = RingBuffer::new(capacity);
+        let (garbage_producer, garbage_consumer) = RingBuffer::new(capacity * GARBAGE_PER_COMMAND);
+
+        let controller = GraphController {
+            sr,
+            slots: vec![],
+            synthetic_inputs: vec![],
+            synthetic_outputs: vec![],
+            cables: vec![],
+            order: vec![],
+            storage_len: 0,
+            capacity,
+            commands: commands_producer,
+            garbage: garbage_consumer,
+        };
+        let processor = GraphProcessor::new(commands_consumer, garbage_producer);
+
+        (controller, processor)
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.sr
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.order
+            .iter()
+            .filter_map(|idx| Some(self.slots[*idx].as_ref()?.id))
+    }
+
+    pub fn node_definition(&self, id: usize) -> Option<&NodeDefinition> {
+        let idx = self.slot_index(id).ok()?;
+        Some(&self.slot(idx).definition)
+    }
+
+    pub fn add_node(
+        &mut self,
+        id: usize,
+        uid: &str,
+        module: &dyn DynamicModule,
+    ) -> Result<(), GraphError> {
+        match uid {
+            SYNTHETIC_INPUT_UID => self.add_input(id),
+            SYNTHETIC_OUTPUT_UID => self.add_output(id),
+            uid => {
+                let invalid_uid = || GraphError::InvalidNodeUid(uid.to_string());
+                let definition = module.info_for_uid(uid).ok_or_else(invalid_uid)?;
+                let node = module.create_node(uid, self.sr).ok_or_else(invalid_uid)?;
+                self.add_node_instance(id, definition.clone(), node)
+            }
+        }
+    }
+
+    pub fn add_node_instance(
+        &mut self,
+        id: usize,
+        definition: NodeDefinition,
+        node: Box<dyn DynNode>,
+    ) -> Result<(), GraphError> {
+        self.collect_garbage();
+        self.check_new_id(id)?;
+        self.reserve(2)?;
+
+        let ports = node_ports(&definition);
+        let info = SlotInfo {
+            id,
+            inputs: ports.inputs().iter().map(|x| x.name()).collect(),
+            outputs: ports.outputs().iter().map(|x| x.name()).collect(),
+            definition,
+        };
+
+        let idx = match self.slots.iter().position(Option::is_none) {
+            Some(idx) => {
+                self.slots[idx] = Some(info);
+                idx
+            }
+            None => {
+                self.slots.push(Some(info));
+                self.slots.len() - 1
+            }
+        };
+
+        // сначала расписание, потому что оно может расширить хранилище узлов
+        let schedule = self.schedule().expect("a new node can't create a cycle");
+        self.send(Command::Swap(schedule))?;
+        self.send(Command::AddNode {
+            slot: idx,
+            node: Box::new(NodeSlot::new(node, ports)),
+        })
+    }
+
+    pub fn add_input(&mut self, id: usize) -> Result<(), GraphError> {
+        self.check_new_id(id)?;
+        self.synthetic_inputs.push(id);
+        Ok(())
+    }
+
+    pub fn add_output(&mut self, id: usize) -> Result<(), GraphError> {
+        self.check_new_id(id)?;
+        self.synthetic_outputs.push(id);
+        Ok(())
+    }
+
+    pub fn connect(&mut self, cable: &Cable) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let Cable { from, to } = cable;
+
+        if self.synthetic_inputs.contains(&from.node_id) {
+            Err(GraphError::UnsupportedInput)?;
+        }
+        let from_slot = self.slot(self.slot_index(from.node_id)?);
+        if !from_slot.outputs.contains(&from.socket_name.as_str()) {
+            Err(unknown_socket(&from.socket_name, from_slot.definition.uid))?;
+        }
+
+        if self.synthetic_outputs.contains(&to.node_id) {
+            output_channel(&to.socket_name)
+                .ok_or_else(|| unknown_socket(&to.socket_name, SYNTHETIC_OUTPUT_UID))?;
+        } else {
+            let to_slot = self.slot(self.slot_index(to.node_id)?);
+            if !to_slot.inputs.contains(&to.socket_name.as_str()) {
+                Err(unknown_socket(&to.socket_name, to_slot.definition.uid))?;
+            }
+        }
+
+        // повторный кабель не должен удваивать сигнал
+        if self.cables.contains(cable) {
+            return Ok(());
+        }
+
+        self.reserve(1)?;
+        self.cables.push(cable.clone());
+        match self.schedule() {
+            Ok(schedule) => self.send(Command::Swap(schedule)),
+            Err(err) => {
+                self.cables.pop();
+                Err(err)
+            }
+        }
+    }
+
+    /// Состояние остальных узлов сохраняется, вместе с узлом удаляются подключенные к нему кабели
+    pub fn remove_node(&mut self, id: usize) -> Result<(), GraphError> {
+        self.collect_garbage();
+
+        if let Some(idx) = self.synthetic_inputs.iter().position(|x| *x == id) {
+            self.synthetic_inputs.remove(idx);
+            return Ok(());
+        }
+
+        if let Some(idx) = self.synthetic_outputs.iter().position(|x| *x == id) {
+            self.reserve(1)?;
+            self.synthetic_outputs.remove(idx);
+            self.remove_cables_of(id);
+            let schedule = self.schedule().expect("removal can't create a cycle");
+            return self.send(Command::Swap(schedule));
+        }
+
+        let idx = self.slot_index(id)?;
+        self.reserve(2)?;
+        self.slots[idx] = None;
+        self.remove_cables_of(id);
+
+        // узел удаляется после того, как расписание перестанет на него ссылаться
+        let schedule = self.schedule().expect("removal can't create a cycle");
+        self.send(Command::Swap(schedule))?;
+        self.send(Command::RemoveNode { slot: idx })
+    }
+
+    pub fn disconnect(&mut self, cable: &Cable) -> Result<(), GraphError> {
+        self.collect_garbage();
+        if !self.cables.contains(cable) {
+            Err(GraphError::UnknownCable(cable.clone()))?;
+        }
+
+        self.reserve(1)?;
+        self.cables.retain(|x| x != cable);
+        let schedule = self.schedule().expect("removal can't create a cycle");
+        self.send(Command::Swap(schedule))
+    }
+
+    /// Значение параметра используется, пока к одноимённому входу не подключен кабель
+    pub fn set_parameter(&mut self, id: usize, name: &str, value: f32) -> Result<(), GraphError> {
+        self.collect_garbage();
+        let idx = self.slot_index(id)?;
+        let slot = self.slot(idx);
+
+        let unknown_parameter =
+            || GraphError::UnknownParameterName(name.to_string(), slot.definition.uid.to_string());
+        if !slot.definition.parameters.iter().any(|x| x.name == name) {
+            Err(unknown_parameter())?;
+        }
+        let input = slot
+            .inputs
+            .iter()
+            .position(|x| *x == name)
+            .ok_or_else(unknown_parameter)?;
+
+        self.reserve(1)?;
+        self.send(Command::SetParameter {
+            slot: idx,
+            input,
+            value,
+        })
+    }
+
+    pub fn reset(&mut self) -> Result<(), GraphError> {
+        self.collect_garbage();
+        self.reserve(1)?;
+
+        self.slots.clear();
+        self.synthetic_inputs.clear();
+        self.synthetic_outputs.clear();
+        self.cables.clear();
+        self.order.clear();
+
+        // пустое хранилище забирает все узлы, они вернутся в контроллер как мусор
+        self.storage_len = 0;
+        self.send(Command::Swap(Box::new(Schedule {
+            storage: Some(vec![]),
+            ..Default::default()
+        })))
+    }
+
+    fn check_new_id(&self, id: usize) -> Result<(), GraphError> {
+        let exists = self.slot_index(id).is_ok()
+            || self.synthetic_inputs.contains(&id)
+            || self.synthetic_outputs.contains(&id);
+
+        if exists {
+            Err(GraphError::DuplicateNodeId(id))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn remove_cables_of(&mut self, id: usize) {
+        self.cables
+            .retain(|x| x.from.node_id != id && x.to.node_id != id);
+    }
+
+    fn slot_index(&self, id: usize) -> Result<usize, GraphError> {
+        self.slots
+            .iter()
+            .position(|x| matches!(x, Some(x) if x.id == id))
+            .ok_or(GraphError::UnknownNodeId(id))
+    }
+
+    fn slot(&self, idx: usize) -> &SlotInfo {
+        self.slots[idx].as_ref().expect("slot index must be valid")
+    }
+
+    // изменение отправляется, только если в очереди хватит места на все его команды,
+    // а в очереди мусора - на всё, что аудиопоток вернёт после их выполнения
+    fn reserve(&self, count: usize) -> Result<(), GraphError> {
+        let pending = self.capacity - self.commands.slots() + count;
+        let free_garbage = self.capacity * GARBAGE_PER_COMMAND - self.garbage.slots();
+        if self.commands.slots() < count || free_garbage < pending * GARBAGE_PER_COMMAND {
+            Err(GraphError::QueueFull)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn send(&mut self, command: Command) -> Result<(), GraphError> {
+        self.commands
+            .push(command)
+            .map_err(|_| GraphError::QueueFull)
+    }
+
+    fn collect_garbage(&mut self) {
+        while let Ok(garbage) = self.garbage.pop() {
+            drop(garbage);
+        }
+    }
+
+    // строит новое расписание после изменения структуры графа
+    fn schedule(&mut self) -> Result<Box<Schedule>, GraphError> {
+        let slot_indices: HashMap<usize, usize> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, slot)| Some((slot.as_ref()?.id, idx)))
+            .collect();
+
+        let mut graph = PetGraph::new();
+        let node_indices: HashMap<usize, _> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(_, slot)| slot.is_some())
+            .map(|(idx, _)| (idx, graph.add_node(idx)))
+            .collect();
+        for Cable { from, to } in &self.cables {
+            if let (Some(from), Some(to)) = (
+                slot_indices.get(&from.node_id),
+                slot_indices.get(&to.node_id),
+            ) {
+                graph.add_edge(node_indices[from], node_indices[to], ());
+            }
+        }
+
+        let order: Vec<usize> = toposort(&graph, None)
+            .map_err(|_| GraphError::CyclicGraph)?
+            .into_iter()
+            .map(|idx| graph[idx])
+            .collect();
+
+        let mut routes: HashMap<usize, Vec<Route>> = HashMap::new();
+        let mut output_routes = vec![];
+
+        for Cable { from, to } in &self.cables {
+            let from_idx = slot_indices[&from.node_id];
+            let source = Source {
+                slot: from_idx,
+                output: self
+                    .slot(from_idx)
+                    .outputs
+                    .iter()
+                    .position(|x| *x == from.socket_name)
+                    .expect("checked in connect"),
+            };
+
+            match slot_indices.get(&to.node_id) {
+                Some(&to_idx) => {
+                    let input = self
+                        .slot(to_idx)
+                        .inputs
+                        .iter()
+                        .position(|x| *x == to.socket_name)
+                        .expect("checked in connect");
+                    // несовместимые сигналы подаются как есть, об этом предупреждает валидация
+                    let conversion = socket_conversion(
+                        &self.slot(from_idx).definition,
+                        &from.socket_name,
+                        &self.slot(to_idx).definition,
+                        &to.socket_name,
+                    )
+                    .unwrap_or(SignalConversion::Identity);
+
+                    // несколько кабелей в один вход суммируются
+                    let node_routes = routes.entry(to_idx).or_default();
+                    let mix = node_routes.iter().any(|x| x.input == input);
+                    node_routes.push(Route {
+                        input,
+                        source,
+                        mix,
+                        conversion,
+                    });
+                }
+                None => output_routes.push(OutputRoute {
+                    channel: output_channel(&to.socket_name).expect("checked in connect"),
+                    source,
+                }),
+            }
+        }
+
+        let storage = if self.slots.len() > self.storage_len {
+            self.storage_len = (self.storage_len * 2).max(self.slots.len()).max(16);
+            Some((0..self.storage_len).map(|_| None).collect())
+        } else {
+            None
+        };
+
+        let steps = order
+            .iter()
+            .map(|idx| Step {
+                slot: *idx,
+                routes: routes.remove(idx).unwrap_or_default(),
+            })
+            .collect();
+
+        self.order = order;
+        Ok(Box::new(Schedule {
+            storage,
+            steps,
+            output_routes,
+        }))
+    }
+}
+
+pub fn node_ports(definition: &NodeDefinition) -> Ports {
+    // параметры тоже являются входами узла
+    let inputs = definition.inputs.iter().map(|x| x.name);
+    let parameters = definition.parameters.iter().map(|x| x.name);
+    let outputs = definition.outputs.iter().map(|x| x.name);
+    Ports::new(inputs.chain(parameters), outputs)
+}
+
+// вход "input" выходного узла попадает во все каналы, а "channel_N" - только в канал N
+fn output_channel(socket_name: &str) -> Option<OutputChannel> {
+    match socket_name {
+        "input" => Some(OutputChannel::All),
+        name => {
+            let idx = name.strip_prefix("channel_")?.parse().ok()?;
+            Some(OutputChannel::Index(idx))
+        }
+    }
+}
+
+fn unknown_socket(socket_name: &str, uid: &str) -> GraphError {
+    GraphError::UnknownSocketName(socket_name.to_string(), uid.to_string())
+}
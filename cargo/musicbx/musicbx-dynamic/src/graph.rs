@@ -0,0 +1,167 @@
+use thiserror::Error;
+
+use musicbx_core::{AudioBuffer, DynNode};
+use musicbx_types::patch::{Cable, Patch};
+use musicbx_types::{NodeDefinition, SubpatchError};
+
+use crate::{DynamicModule, GraphController, GraphProcessor};
+
+// все изменения сразу применяются, поэтому очередь почти всегда пуста
+const QUEUE_CAPACITY: usize = 16;
+
+/// Граф, который редактируется и обрабатывается в одном потоке
+pub struct Graph {
+    controller: GraphController,
+    processor: GraphProcessor,
+}
+
+#[derive(Debug, Error)]
+pub enum GraphError {
+    #[error("Invalid node UID: {0}")]
+    InvalidNodeUid(String),
+
+    #[error("Duplicate node id {0}")]
+    DuplicateNodeId(usize),
+
+    #[error("Unknown node id {0}")]
+    UnknownNodeId(usize),
+
+    #[error("Unknown socket name {0} for node with uid {1}")]
+    UnknownSocketName(String, String),
+
+    #[error("Unknown parameter name {0} for node with uid {1}")]
+    UnknownParameterName(String, String),
+
+    #[error("Invalid parameter value: {0}")]
+    InvalidParameterValue(String),
+
+    #[error("Unknown cable {0:?}")]
+    UnknownCable(Cable),
+
+    #[error("Patch inputs are not supported")]
+    UnsupportedInput,
+
+    #[error("A cyclic graph is not supported")]
+    CyclicGraph,
+
+    #[error("The audio thread doesn't keep up with graph changes")]
+    QueueFull,
+
+    #[error(transparent)]
+    InvalidSubpatch(#[from] SubpatchError),
+}
+
+impl Graph {
+    pub fn new(sr: f32) -> Graph {
+        let (controller, processor) = GraphController::new(sr, QUEUE_CAPACITY);
+        Graph {
+            controller,
+            processor,
+        }
+    }
+
+    pub fn from_patch(
+        patch: &Patch,
+        module: &dyn DynamicModule,
+        sr: f32,
+    ) -> Result<Graph, GraphError> {
+        let mut graph = Graph::new(sr);
+
+        for node in &patch.nodes {
+            graph.add_node(node.id, &node.uid, module)?;
+
+            for (name, value) in &node.parameters {
+                let value: f32 = value
+                    .parse()
+                    .map_err(|_| GraphError::InvalidParameterValue(value.to_string()))?;
+                let value = graph
+                    .controller
+                    .node_definition(node.id)
+                    .and_then(|x| x.parameters.iter().find(|x| x.name == name))
+                    .map(|x| x.denormalize(value))
+                    .ok_or_else(|| {
+                        GraphError::UnknownParameterName(name.to_string(), node.uid.to_string())
+                    })?;
+                graph.set_parameter(node.id, name, value)?;
+            }
+        }
+
+        for cable in &patch.cables {
+            graph.connect(cable)?;
+        }
+
+        Ok(graph)
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.controller.sample_rate()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.controller.node_ids()
+    }
+
+    pub fn add_node(
+        &mut self,
+        id: usize,
+        uid: &str,
+        module: &dyn DynamicModule,
+    ) -> Result<(), GraphError> {
+        self.controller.add_node(id, uid, module)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn add_node_instance(
+        &mut self,
+        id: usize,
+        definition: NodeDefinition,
+        node: Box<dyn DynNode>,
+    ) -> Result<(), GraphError> {
+        self.controller.add_node_instance(id, definition, node)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn add_input(&mut self, id: usize) -> Result<(), GraphError> {
+        self.controller.add_input(id)
+    }
+
+    pub fn add_output(&mut self, id: usize) -> Result<(), GraphError> {
+        self.controller.add_output(id)
+    }
+
+    pub fn connect(&mut self, cable: &Cable) -> Result<(), GraphError> {
+        self.controller.connect(cable)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn remove_node(&mut self, id: usize) -> Result<(), GraphError> {
+        self.controller.remove_node(id)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn disconnect(&mut self, cable: &Cable) -> Result<(), GraphError> {
+        self.controller.disconnect(cable)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn set_parameter(&mut self, id: usize, name: &str, value: f32) -> Result<(), GraphError> {
+        self.controller.set_parameter(id, name, value)?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn reset(&mut self) -> Result<(), GraphError> {
+        self.controller.reset()?;
+        self.processor.receive();
+        Ok(())
+    }
+
+    pub fn process(&mut self, n: usize, output: &mut AudioBuffer) {
+        self.processor.process(n, output)
+    }
+}
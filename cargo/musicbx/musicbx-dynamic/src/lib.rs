@@ -0,0 +1,15 @@
+pub use controller::{node_ports, GraphController};
+pub use graph::{Graph, GraphError};
+pub use module::DynamicModule;
+pub use processor::GraphProcessor;
+pub use render::{render_patch_to_wav, render_to_wav, RenderError};
+pub use stream::BlockStream;
+pub use subpatch::PatchModule;
+
+mod controller;
+mod graph;
+mod module;
+mod processor;
+mod render;
+mod stream;
+mod subpatch;
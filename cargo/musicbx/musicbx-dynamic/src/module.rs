@@ -0,0 +1,55 @@
+use musicbx_core::{DynNode, FromSampleRate};
+use musicbx_std::{filter, osc, util, StdModuleDefinition};
+use musicbx_types::ModuleDefinition;
+
+type NodeConstructor = fn(f32) -> Box<dyn DynNode>;
+
+pub trait DynamicModule: ModuleDefinition {
+    fn create_node(&self, uid: &str, sr: f32) -> Option<Box<dyn DynNode>>;
+}
+
+impl DynamicModule for StdModuleDefinition {
+    fn create_node(&self, uid: &str, sr: f32) -> Option<Box<dyn DynNode>> {
+        STD_CONSTRUCTORS
+            .iter()
+            .find(|(x, _)| *x == uid)
+            .map(|(_, constructor)| constructor(sr))
+    }
+}
+
+static STD_CONSTRUCTORS: &[(&str, NodeConstructor)] = &[
+    (
+        filter::LP12Filter::definition().uid,
+        create::<filter::LP12Filter>,
+    ),
+    (osc::Lfo::definition().uid, create::<osc::Lfo>),
+    (
+        osc::SimpleSawOsc::definition().uid,
+        create::<osc::SimpleSawOsc>,
+    ),
+    (osc::SinOsc::definition().uid, create::<osc::SinOsc>),
+    (util::Add::definition().uid, create::<util::Add>),
+    (util::Amp::definition().uid, create::<util::Amp>),
+    (util::ConstSig::definition().uid, create::<util::ConstSig>),
+    (
+        util::FreqToPitch::definition().uid,
+        create::<util::FreqToPitch>,
+    ),
+    (util::HardClip::definition().uid, create::<util::HardClip>),
+    (util::Mul::definition().uid, create::<util::Mul>),
+    (
+        util::PitchToFreq::definition().uid,
+        create::<util::PitchToFreq>,
+    ),
+    (
+        util::UniformRandom::definition().uid,
+        create::<util::UniformRandom>,
+    ),
+];
+
+fn create<T>(sr: f32) -> Box<dyn DynNode>
+where
+    T: DynNode + FromSampleRate + 'static,
+{
+    Box::new(T::from_sample_rate(sr))
+}
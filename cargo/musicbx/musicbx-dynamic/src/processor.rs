@@ -0,0 +1,261 @@
+use std::mem;
+
+use rtrb::{Consumer, Producer};
+
+use musicbx_core::{AudioBuffer, DynNode, Ports, MAX_BLOCK_SIZE};
+use musicbx_types::SignalConversion;
+
+/// Часть графа, которая живёт в аудиопотоке.
+/// Изменения приходят от [`GraphController`](crate::GraphController) и применяются между блоками
+pub struct GraphProcessor {
+    slots: Vec<Option<Box<NodeSlot>>>,
+    schedule: Box<Schedule>,
+    commands: Consumer<Command>,
+    garbage: Producer<Garbage>,
+}
+
+pub(crate) struct NodeSlot {
+    pub node: Box<dyn DynNode>,
+    pub ports: Ports,
+    // значения параметров по индексам входов
+    pub parameters: Vec<Option<f32>>,
+}
+
+#[derive(Default)]
+pub(crate) struct Schedule {
+    // новое хранилище узлов, если старого уже не хватает
+    pub storage: Option<Vec<Option<Box<NodeSlot>>>>,
+    pub steps: Vec<Step>,
+    pub output_routes: Vec<OutputRoute>,
+}
+
+pub(crate) struct Step {
+    pub slot: usize,
+    pub routes: Vec<Route>,
+}
+
+pub(crate) struct Route {
+    pub input: usize,
+    pub source: Source,
+    // прибавить к уже скопированному в вход сигналу
+    pub mix: bool,
+    // например, из высоты тона в частоту, если сокеты ждут разные сигналы
+    pub conversion: SignalConversion,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Source {
+    pub slot: usize,
+    pub output: usize,
+}
+
+pub(crate) struct OutputRoute {
+    pub channel: OutputChannel,
+    pub source: Source,
+}
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum OutputChannel {
+    All,
+    Index(usize),
+}
+
+pub(crate) enum Command {
+    AddNode {
+        slot: usize,
+        node: Box<NodeSlot>,
+    },
+    RemoveNode {
+        slot: usize,
+    },
+    SetParameter {
+        slot: usize,
+        input: usize,
+        value: f32,
+    },
+    Swap(Box<Schedule>),
+}
+
+/// Сколько мусора может вернуть одна команда: Swap возвращает расписание и хранилище узлов
+pub(crate) const GARBAGE_PER_COMMAND: usize = 2;
+
+// память освобождается в потоке контроллера, а не в аудиопотоке
+#[allow(dead_code)]
+pub(crate) enum Garbage {
+    Node(Box<NodeSlot>),
+    Schedule(Box<Schedule>),
+    Storage(Vec<Option<Box<NodeSlot>>>),
+}
+
+impl GraphProcessor {
+    pub(crate) fn new(commands: Consumer<Command>, garbage: Producer<Garbage>) -> Self {
+        GraphProcessor {
+            slots: vec![],
+            schedule: Box::default(),
+            commands,
+            garbage,
+        }
+    }
+
+    /// Применяет все полученные изменения, не блокируясь
+    pub fn receive(&mut self) {
+        while let Ok(command) = self.commands.pop() {
+            match command {
+                Command::AddNode { slot, node } => {
+                    if let Some(x) = self.slots.get_mut(slot) {
+                        let old = x.replace(node);
+                        self.dispose_node(old);
+                        self.update_inputs();
+                    }
+                }
+                Command::RemoveNode { slot } => {
+                    if let Some(x) = self.slots.get_mut(slot) {
+                        let old = x.take();
+                        self.dispose_node(old);
+                    }
+                }
+                Command::SetParameter { slot, input, value } => {
+                    if let Some(Some(slot)) = self.slots.get_mut(slot) {
+                        slot.set_parameter(input, value);
+                    }
+                }
+                Command::Swap(mut schedule) => {
+                    if let Some(mut storage) = schedule.storage.take() {
+                        for (new, old) in storage.iter_mut().zip(&mut self.slots) {
+                            mem::swap(new, old);
+                        }
+                        let old = mem::replace(&mut self.slots, storage);
+                        self.dispose(Garbage::Storage(old));
+                    }
+
+                    let old = mem::replace(&mut self.schedule, schedule);
+                    self.dispose(Garbage::Schedule(old));
+                    self.update_inputs();
+                }
+            }
+        }
+    }
+
+    /// Каналы, которых нет в `output`, отбрасываются
+    pub fn process(&mut self, n: usize, output: &mut AudioBuffer) {
+        assert!(
+            n <= MAX_BLOCK_SIZE,
+            "Block size {n} exceeds {MAX_BLOCK_SIZE}"
+        );
+        assert!(
+            n <= output.len(),
+            "Block size {n} exceeds output length {}",
+            output.len()
+        );
+
+        self.receive();
+
+        for step in &self.schedule.steps {
+            // входы заполняются из выходов других узлов, поэтому порты узла временно забираются
+            let mut ports = match &mut self.slots[step.slot] {
+                Some(slot) => mem::take(&mut slot.ports),
+                None => continue,
+            };
+
+            for route in &step.routes {
+                let source = &self.slots[route.source.slot];
+                let buffer = ports.input_mut(route.input).audio_mut();
+                if let (Some(source), Some(buffer)) = (source, buffer) {
+                    let source = source.ports.outputs()[route.source.output].buffer();
+                    let conversion = route.conversion;
+                    if route.mix {
+                        for (sample, x) in buffer[..n].iter_mut().zip(&source[..n]) {
+                            *sample += conversion.apply(*x);
+                        }
+                    } else if conversion == SignalConversion::Identity {
+                        buffer[..n].copy_from_slice(&source[..n]);
+                    } else {
+                        for (sample, x) in buffer[..n].iter_mut().zip(&source[..n]) {
+                            *sample = conversion.apply(*x);
+                        }
+                    }
+                }
+            }
+
+            if let Some(slot) = &mut self.slots[step.slot] {
+                slot.node.process_block(n, &mut ports);
+                slot.ports = ports;
+            }
+        }
+
+        output.fill(0.0);
+        for OutputRoute { channel, source } in &self.schedule.output_routes {
+            let buffer = match &self.slots[source.slot] {
+                Some(slot) => &slot.ports.outputs()[source.output].buffer()[..n],
+                None => continue,
+            };
+            let channels = match *channel {
+                OutputChannel::All => 0..output.channels(),
+                OutputChannel::Index(idx) => idx..(idx + 1).min(output.channels()),
+            };
+
+            for channel in channels {
+                for (sample, x) in output.channel_mut(channel).iter_mut().zip(buffer) {
+                    *sample += x;
+                }
+            }
+        }
+    }
+
+    fn update_inputs(&mut self) {
+        for step in &self.schedule.steps {
+            if let Some(slot) = &mut self.slots[step.slot] {
+                slot.update_inputs(&step.routes);
+            }
+        }
+    }
+
+    fn dispose_node(&mut self, node: Option<Box<NodeSlot>>) {
+        if let Some(node) = node {
+            self.dispose(Garbage::Node(node));
+        }
+    }
+
+    fn dispose(&mut self, garbage: Garbage) {
+        // контроллер не отправляет команды, пока для их мусора нет места,
+        // но даже если очередь переполнится, память не освобождается в аудиопотоке
+        if let Err(err) = self.garbage.push(garbage) {
+            debug_assert!(false, "Garbage queue is full");
+            mem::forget(err);
+        }
+    }
+}
+
+impl NodeSlot {
+    pub fn new(node: Box<dyn DynNode>, ports: Ports) -> Self {
+        NodeSlot {
+            node,
+            parameters: vec![None; ports.inputs().len()],
+            ports,
+        }
+    }
+
+    fn set_parameter(&mut self, input: usize, value: f32) {
+        self.parameters[input] = Some(value);
+
+        // подключенный кабель важнее значения параметра
+        let port = self.ports.input_mut(input);
+        if !port.is_audio() {
+            port.set_float(value);
+        }
+    }
+
+    fn update_inputs(&mut self, routes: &[Route]) {
+        for input in 0..self.ports.inputs().len() {
+            let port = self.ports.input_mut(input);
+
+            if routes.iter().any(|x| x.input == input) {
+                port.set_audio();
+            } else if let Some(value) = self.parameters[input] {
+                port.set_float(value);
+            } else {
+                port.reset();
+            }
+        }
+    }
+}
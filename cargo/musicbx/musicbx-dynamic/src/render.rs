@@ -0,0 +1,66 @@
+use std::path::Path;
+use std::time::Duration;
+
+use hound::{SampleFormat, WavSpec, WavWriter};
+use thiserror::Error;
+
+use musicbx_core::{AudioBuffer, MAX_BLOCK_SIZE};
+use musicbx_types::patch::Patch;
+
+use crate::{DynamicModule, Graph, GraphError};
+
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error(transparent)]
+    Graph(#[from] GraphError),
+
+    #[error(transparent)]
+    Wav(#[from] hound::Error),
+}
+
+/// Обрабатывает патч быстрее реального времени и сохраняет результат в WAV-файл
+pub fn render_patch_to_wav(
+    patch: &Patch,
+    module: &dyn DynamicModule,
+    sr: u32,
+    channels: u16,
+    duration: Duration,
+    path: impl AsRef<Path>,
+) -> Result<(), RenderError> {
+    let mut graph = Graph::from_patch(patch, module, sr as f32)?;
+    render_to_wav(&mut graph, channels, duration, path)
+}
+
+pub fn render_to_wav(
+    graph: &mut Graph,
+    channels: u16,
+    duration: Duration,
+    path: impl AsRef<Path>,
+) -> Result<(), RenderError> {
+    let spec = WavSpec {
+        channels,
+        sample_rate: graph.sample_rate() as u32,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(path, spec)?;
+
+    let mut remaining = (duration.as_secs_f64() * graph.sample_rate() as f64).round() as usize;
+    let mut block = AudioBuffer::new(channels as usize, MAX_BLOCK_SIZE);
+
+    while remaining > 0 {
+        let n = remaining.min(MAX_BLOCK_SIZE);
+        graph.process(n, &mut block);
+
+        for idx in 0..n {
+            for sample in block.frame(idx) {
+                writer.write_sample(sample)?;
+            }
+        }
+
+        remaining -= n;
+    }
+
+    writer.finalize()?;
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use musicbx_core::AudioBuffer;
+
+/// Переносит сэмплы из обрабатываемых блоками графа в чередующийся буфер звуковой карты.
+/// Размер буфера в колбэке не совпадает с размером блока, поэтому остаток блока переживает вызов
+pub struct BlockStream {
+    block: AudioBuffer,
+    block_size: usize,
+    position: usize,
+}
+
+impl BlockStream {
+    pub fn new(channels: usize, block_size: usize) -> Self {
+        BlockStream {
+            block: AudioBuffer::new(channels, block_size),
+            block_size,
+            position: block_size,
+        }
+    }
+
+    /// `process` вызывается с размером блока, когда все сэмплы предыдущего блока уже отданы
+    pub fn fill<T>(
+        &mut self,
+        data: &mut [T],
+        mut process: impl FnMut(usize, &mut AudioBuffer),
+        convert: impl Fn(f32) -> T,
+    ) {
+        for frame in data.chunks_mut(self.block.channels()) {
+            if self.position == self.block_size {
+                process(self.block_size, &mut self.block);
+                self.position = 0;
+            }
+
+            for (sample, x) in frame.iter_mut().zip(self.block.frame(self.position)) {
+                *sample = convert(x);
+            }
+            self.position += 1;
+        }
+    }
+}
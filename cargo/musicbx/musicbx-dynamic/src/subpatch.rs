@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, HashMap};
+
+use petgraph::algo::toposort;
+use petgraph::Graph as PetGraph;
+
+use musicbx_core::{DataMut, DataRef, DynNode, Ports, MAX_BLOCK_SIZE};
+use musicbx_types::patch::{Cable, Node, Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+use musicbx_types::{
+    dependency_order, exposed_parameters, patch_definition, socket_conversion, ModuleDefinition,
+    NodeDefinition, SignalConversion,
+};
+
+use crate::{node_ports, DynamicModule, GraphError};
+
+/// Модуль, в котором патчи становятся узлами. Как и в кодогенерации,
+/// узел ссылается на подпатч его именем
+pub struct PatchModule<'a> {
+    base: &'a dyn DynamicModule,
+    subpatches: Vec<(NodeDefinition, SubpatchLayout)>,
+}
+
+impl<'a> PatchModule<'a> {
+    pub fn new(
+        base: &'a dyn DynamicModule,
+        mut patches: BTreeMap<String, Patch>,
+    ) -> Result<PatchModule<'a>, GraphError> {
+        let mut module = PatchModule {
+            base,
+            subpatches: vec![],
+        };
+
+        let order: Vec<String> = dependency_order(&patches)?
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        for name in order {
+            let patch = patches
+                .remove(&name)
+                .expect("ordered names come from patches");
+            let definition = patch_definition(&name, &patch, &module)?;
+            // как и в кодогенерации, мёртвые узлы не создаются
+            let patch = patch.without_dead_nodes();
+
+            // ошибки в структуре подпатча находятся сейчас, а не при создании узла
+            let layout = SubpatchLayout::new(&patch, &definition, &module)?;
+            module.subpatches.push((definition, layout));
+        }
+
+        Ok(module)
+    }
+}
+
+impl ModuleDefinition for PatchModule<'_> {
+    /// Подпатчи, а за ними узлы базового модуля, которые подпатчи не заслоняют
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        let mut definitions: Vec<&NodeDefinition> = self
+            .subpatches
+            .iter()
+            .map(|(definition, _)| definition)
+            .collect();
+        for definition in self.base.definitions() {
+            if definitions.iter().all(|x| x.uid != definition.uid) {
+                definitions.push(definition);
+            }
+        }
+        definitions
+    }
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        self.subpatches
+            .iter()
+            .map(|(definition, _)| definition)
+            .find(|x| x.uid == uid)
+            .or_else(|| self.base.info_for_uid(uid))
+    }
+}
+
+impl DynamicModule for PatchModule<'_> {
+    fn create_node(&self, uid: &str, sr: f32) -> Option<Box<dyn DynNode>> {
+        match self.subpatches.iter().find(|(x, _)| x.uid == uid) {
+            Some((_, layout)) => Some(Box::new(SubpatchNode::new(layout, self, sr)?)),
+            None => self.base.create_node(uid, sr),
+        }
+    }
+}
+
+/// Патч, который обрабатывается как один узел. Входы и выходы узла - синтетические узлы патча
+struct SubpatchNode {
+    // узлы в порядке шагов `layout`
+    nodes: Vec<Box<dyn DynNode>>,
+    layout: SubpatchLayout,
+}
+
+/// Порядок узлов подпатча, их порты и маршруты между ними. От частоты дискретизации
+/// не зависит, поэтому проверяется один раз, при создании модуля
+#[derive(Clone)]
+struct SubpatchLayout {
+    steps: Vec<SubpatchStep>,
+    // источники для каждого выхода узла
+    outputs: Vec<Vec<SubpatchSource>>,
+    // значения входов узла без кабеля и параметра
+    defaults: Vec<f32>,
+}
+
+#[derive(Clone)]
+struct SubpatchStep {
+    uid: String,
+    ports: Ports,
+    // источники для каждого подключенного входа
+    routes: Vec<(usize, Vec<SubpatchSource>)>,
+}
+
+#[derive(Copy, Clone)]
+enum SubpatchSource {
+    Input(usize),
+    // как и в кодогенерации, сигнал между внутренними узлами приводится к типу входа
+    Node {
+        step: usize,
+        output: usize,
+        conversion: SignalConversion,
+    },
+}
+
+impl SubpatchNode {
+    /// None, если модуль не создаёт узел, описание которого у него есть
+    fn new(layout: &SubpatchLayout, module: &dyn DynamicModule, sr: f32) -> Option<SubpatchNode> {
+        let nodes = layout
+            .steps
+            .iter()
+            .map(|step| module.create_node(&step.uid, sr))
+            .collect::<Option<_>>()?;
+        Some(SubpatchNode {
+            nodes,
+            layout: layout.clone(),
+        })
+    }
+}
+
+impl SubpatchLayout {
+    fn new(
+        patch: &Patch,
+        definition: &NodeDefinition,
+        module: &dyn DynamicModule,
+    ) -> Result<SubpatchLayout, GraphError> {
+        let outer_ports = node_ports(definition);
+        let nodes: HashMap<usize, &Node> = patch.nodes.iter().map(|x| (x.id, x)).collect();
+        let node = |id| nodes.get(&id).copied().ok_or(GraphError::UnknownNodeId(id));
+
+        let mut graph = PetGraph::new();
+        let indices: HashMap<usize, _> = patch
+            .nodes
+            .iter()
+            .filter(|x| x.uid != SYNTHETIC_INPUT_UID && x.uid != SYNTHETIC_OUTPUT_UID)
+            .map(|x| (x.id, graph.add_node(x.id)))
+            .collect();
+        for Cable { from, to } in &patch.cables {
+            if let (Some(from), Some(to)) = (indices.get(&from.node_id), indices.get(&to.node_id)) {
+                graph.add_edge(*from, *to, ());
+            }
+        }
+        let order: Vec<usize> = toposort(&graph, None)
+            .map_err(|_| GraphError::CyclicGraph)?
+            .into_iter()
+            .map(|idx| graph[idx])
+            .collect();
+
+        let mut steps = vec![];
+        for id in &order {
+            let inner = node(*id)?;
+            let invalid_uid = || GraphError::InvalidNodeUid(inner.uid.clone());
+            let inner_definition = module.info_for_uid(&inner.uid).ok_or_else(invalid_uid)?;
+            let mut ports = node_ports(inner_definition);
+
+            for (name, value) in &inner.parameters {
+                let definition = inner_definition
+                    .parameters
+                    .iter()
+                    .find(|x| x.name == name)
+                    .ok_or_else(|| {
+                        GraphError::UnknownParameterName(name.clone(), inner.uid.clone())
+                    })?;
+                let value: f32 = value
+                    .parse()
+                    .map_err(|_| GraphError::InvalidParameterValue(value.clone()))?;
+                let input = ports.input_index(name).expect("parameters are inputs");
+                ports
+                    .input_mut(input)
+                    .set_float(definition.denormalize(value));
+            }
+
+            steps.push(SubpatchStep {
+                uid: inner.uid.clone(),
+                ports,
+                routes: vec![],
+            });
+        }
+
+        let mut outputs = vec![vec![]; outer_ports.outputs().len()];
+        for Cable { from, to } in &patch.cables {
+            let from_node = node(from.node_id)?;
+            let source = if from_node.uid == SYNTHETIC_INPUT_UID {
+                let name = from_node.port_name(&from.socket_name);
+                SubpatchSource::Input(
+                    outer_ports
+                        .input_index(name)
+                        .expect("inputs come from cables"),
+                )
+            } else {
+                let step = order
+                    .iter()
+                    .position(|x| *x == from.node_id)
+                    .expect("ordered");
+                let output = steps[step]
+                    .ports
+                    .output_index(&from.socket_name)
+                    .ok_or_else(|| unknown_socket(&from.socket_name, &from_node.uid))?;
+                SubpatchSource::Node {
+                    step,
+                    output,
+                    conversion: SignalConversion::Identity,
+                }
+            };
+
+            let to_node = node(to.node_id)?;
+            if to_node.uid == SYNTHETIC_OUTPUT_UID {
+                let name = to_node.port_name(&to.socket_name);
+                let output = outer_ports
+                    .output_index(name)
+                    .expect("outputs come from cables");
+                outputs[output].push(source);
+            } else if to_node.uid == SYNTHETIC_INPUT_UID {
+                Err(unknown_socket(&to.socket_name, SYNTHETIC_INPUT_UID))?;
+            } else {
+                let step = order
+                    .iter()
+                    .position(|x| *x == to.node_id)
+                    .expect("ordered");
+                let source = match source {
+                    SubpatchSource::Node { step, output, .. } => {
+                        let from_definition = module
+                            .info_for_uid(&from_node.uid)
+                            .ok_or_else(|| GraphError::InvalidNodeUid(from_node.uid.clone()))?;
+                        let to_definition = module
+                            .info_for_uid(&to_node.uid)
+                            .ok_or_else(|| GraphError::InvalidNodeUid(to_node.uid.clone()))?;
+                        // несовместимые сигналы подаются как есть, об этом предупреждает валидация
+                        let conversion = socket_conversion(
+                            from_definition,
+                            &from.socket_name,
+                            to_definition,
+                            &to.socket_name,
+                        )
+                        .unwrap_or(SignalConversion::Identity);
+                        SubpatchSource::Node {
+                            step,
+                            output,
+                            conversion,
+                        }
+                    }
+                    source => source,
+                };
+                add_route(&mut steps[step], &to.socket_name, &to_node.uid, source)?;
+            }
+        }
+
+        // выставленные наружу параметры подключаются ко входам узла, как кабели
+        for (inner, parameter, name) in exposed_parameters(patch) {
+            let source = SubpatchSource::Input(outer_ports.input_index(name).expect("exposed"));
+            let step = order.iter().position(|x| *x == inner.id).expect("ordered");
+            add_route(&mut steps[step], parameter, &inner.uid, source)?;
+        }
+
+        // выставленный наружу параметр без значения берёт значение по умолчанию из патча
+        let defaults = outer_ports
+            .inputs()
+            .iter()
+            .map(|port| {
+                definition
+                    .parameters
+                    .iter()
+                    .find(|x| x.name == port.name())
+                    .and_then(|x| x.default.parse().ok())
+                    .unwrap_or(0.0)
+            })
+            .collect();
+
+        Ok(SubpatchLayout {
+            steps,
+            outputs,
+            defaults,
+        })
+    }
+}
+
+impl DynNode for SubpatchNode {
+    fn process_block(&mut self, n: usize, ports: &mut Ports) {
+        assert!(
+            n <= MAX_BLOCK_SIZE,
+            "Block size {n} exceeds {MAX_BLOCK_SIZE}"
+        );
+
+        let SubpatchLayout {
+            steps,
+            outputs: output_sources,
+            defaults,
+        } = &mut self.layout;
+        let (inputs, outputs) = ports.split_mut();
+        let input_data = |idx: usize| inputs[idx].data(n).unwrap_or(DataRef::Float(defaults[idx]));
+
+        for (idx, node) in self.nodes.iter_mut().enumerate() {
+            // входы узла заполняются из выходов предыдущих узлов
+            let (done, rest) = steps.split_at_mut(idx);
+            let step = &mut rest[0];
+
+            for (input, sources) in &step.routes {
+                let buffer = step
+                    .ports
+                    .input_mut(*input)
+                    .audio_mut()
+                    .expect("routed inputs are audio");
+                mix_sources(&mut buffer[..n], sources, done, &input_data);
+            }
+
+            node.process_block(n, &mut step.ports);
+        }
+
+        for (output, sources) in outputs.iter_mut().zip(output_sources.iter()) {
+            if let DataMut::Audio(buffer) = output.data_mut(n) {
+                mix_sources(buffer, sources, steps, &input_data);
+            }
+        }
+    }
+}
+
+fn add_route(
+    step: &mut SubpatchStep,
+    socket_name: &str,
+    uid: &str,
+    source: SubpatchSource,
+) -> Result<(), GraphError> {
+    let input = step
+        .ports
+        .input_index(socket_name)
+        .ok_or_else(|| unknown_socket(socket_name, uid))?;
+    step.ports.input_mut(input).set_audio();
+
+    match step.routes.iter_mut().find(|(x, _)| *x == input) {
+        Some((_, sources)) => sources.push(source),
+        None => step.routes.push((input, vec![source])),
+    }
+    Ok(())
+}
+
+// несколько кабелей в один вход суммируются
+fn mix_sources<'a>(
+    buffer: &mut [f32],
+    sources: &[SubpatchSource],
+    steps: &[SubpatchStep],
+    input_data: &impl Fn(usize) -> DataRef<'a>,
+) {
+    buffer.fill(0.0);
+    for source in sources {
+        let (data, conversion) = match *source {
+            SubpatchSource::Input(idx) => (input_data(idx), SignalConversion::Identity),
+            SubpatchSource::Node {
+                step,
+                output,
+                conversion,
+            } => (
+                DataRef::Audio(steps[step].ports.outputs()[output].buffer()),
+                conversion,
+            ),
+        };
+        for (i, sample) in buffer.iter_mut().enumerate() {
+            *sample += conversion.apply(data[i]);
+        }
+    }
+}
+
+fn unknown_socket(socket_name: &str, uid: &str) -> GraphError {
+    GraphError::UnknownSocketName(socket_name.to_string(), uid.to_string())
+}
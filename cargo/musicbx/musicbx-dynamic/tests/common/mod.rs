@@ -0,0 +1,30 @@
+// у каждого файла с тестами своя копия модуля, и не все используют все функции
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use musicbx_types::patch::{Cable, CableEnd, GridOffset, Node};
+
+pub fn node(id: usize, uid: &str) -> Node {
+    Node {
+        id,
+        uid: uid.to_string(),
+        offset: GridOffset { x: 0, y: 0 },
+        parameters: HashMap::new(),
+        collapsed: false,
+        label: None,
+        port: None,
+        exposed: HashMap::new(),
+    }
+}
+
+pub fn cable(from: usize, output: &str, to: usize, input: &str) -> Cable {
+    let end = |node_id, socket_name: &str| CableEnd {
+        node_id,
+        socket_name: socket_name.to_string(),
+    };
+    Cable {
+        from: end(from, output),
+        to: end(to, input),
+    }
+}
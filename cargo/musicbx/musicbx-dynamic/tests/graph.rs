@@ -0,0 +1,120 @@
+use musicbx_core::AudioBuffer;
+use musicbx_dynamic::{GraphController, GraphProcessor};
+use musicbx_std::{osc, util, StdModuleDefinition};
+use musicbx_types::patch::SYNTHETIC_OUTPUT_UID;
+
+use common::cable;
+
+mod common;
+
+const SR: f32 = 48000.0;
+const BLOCK_SIZE: usize = 64;
+const OUTPUT_ID: usize = 0;
+
+fn graph() -> (GraphController, GraphProcessor) {
+    let (mut controller, processor) = GraphController::new(SR, 16);
+    controller
+        .add_node(OUTPUT_ID, SYNTHETIC_OUTPUT_UID, &StdModuleDefinition)
+        .unwrap();
+    (controller, processor)
+}
+
+fn render(processor: &mut GraphProcessor, channels: usize) -> AudioBuffer {
+    let mut block = AudioBuffer::new(channels, BLOCK_SIZE);
+    processor.process(BLOCK_SIZE, &mut block);
+    block
+}
+
+#[test]
+fn parameter_changes_apply_at_the_next_block() {
+    let (mut controller, mut processor) = graph();
+    let constant = util::ConstSig::definition().uid;
+    controller
+        .add_node(1, constant, &StdModuleDefinition)
+        .unwrap();
+    controller.set_parameter(1, "value", 0.5).unwrap();
+    controller
+        .connect(&cable(1, "output", OUTPUT_ID, "input"))
+        .unwrap();
+    assert!(render(&mut processor, 1)
+        .channel(0)
+        .iter()
+        .all(|x| *x == 0.5));
+
+    // новое значение действует с первого сэмпла следующего блока
+    controller.set_parameter(1, "value", 0.25).unwrap();
+    assert!(render(&mut processor, 1)
+        .channel(0)
+        .iter()
+        .all(|x| *x == 0.25));
+}
+
+// первый генератор звучит в канале 0, второй - в канале 1
+fn two_oscillators() -> (GraphController, GraphProcessor) {
+    let (mut controller, processor) = graph();
+    let sin = osc::SinOsc::definition().uid;
+    for (id, freq) in [(1, 440.0), (2, 1000.0)] {
+        controller.add_node(id, sin, &StdModuleDefinition).unwrap();
+        controller.set_parameter(id, "freq", freq).unwrap();
+        let channel = format!("channel_{}", id - 1);
+        controller
+            .connect(&cable(id, "output", OUTPUT_ID, &channel))
+            .unwrap();
+    }
+    (controller, processor)
+}
+
+fn first_channel(processor: &mut GraphProcessor, blocks: usize) -> Vec<f32> {
+    (0..blocks)
+        .flat_map(|_| render(processor, 2).channel(0).to_vec())
+        .collect()
+}
+
+#[test]
+fn removing_nodes_and_cables_keeps_other_nodes_state() {
+    let (_controller, mut processor) = two_oscillators();
+    let expected = first_channel(&mut processor, 8);
+
+    let (mut controller, mut processor) = two_oscillators();
+    let mut actual = first_channel(&mut processor, 3);
+    controller
+        .disconnect(&cable(2, "output", OUTPUT_ID, "channel_1"))
+        .unwrap();
+    actual.extend(first_channel(&mut processor, 2));
+    controller.remove_node(2).unwrap();
+    actual.extend(first_channel(&mut processor, 3));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn cables_into_one_input_are_summed() {
+    let (mut controller, mut processor) = graph();
+    let constant = util::ConstSig::definition().uid;
+    for (id, value) in [(1, 0.25), (2, 0.5)] {
+        controller
+            .add_node(id, constant, &StdModuleDefinition)
+            .unwrap();
+        controller.set_parameter(id, "value", value).unwrap();
+    }
+    let clip = util::HardClip::definition().uid;
+    controller.add_node(3, clip, &StdModuleDefinition).unwrap();
+    controller
+        .connect(&cable(3, "output", OUTPUT_ID, "input"))
+        .unwrap();
+
+    controller.connect(&cable(1, "output", 3, "input")).unwrap();
+    controller.connect(&cable(2, "output", 3, "input")).unwrap();
+    assert!(render(&mut processor, 1)
+        .channel(0)
+        .iter()
+        .all(|x| *x == 0.75));
+
+    controller
+        .disconnect(&cable(1, "output", 3, "input"))
+        .unwrap();
+    assert!(render(&mut processor, 1)
+        .channel(0)
+        .iter()
+        .all(|x| *x == 0.5));
+}
@@ -0,0 +1,26 @@
+use musicbx_core::AudioBuffer;
+use musicbx_dynamic::BlockStream;
+
+#[test]
+fn blocks_are_interleaved_across_callbacks() {
+    let mut counter = 0.0;
+    let mut process = |n: usize, block: &mut AudioBuffer| {
+        for idx in 0..n {
+            block.channel_mut(0)[idx] = counter;
+            block.channel_mut(1)[idx] = -counter;
+            counter += 1.0;
+        }
+    };
+
+    // размер буфера колбэка не кратен размеру блока
+    let mut blocks = BlockStream::new(2, 4);
+    let mut data = vec![];
+    for _ in 0..3 {
+        let mut callback = [0.0; 6];
+        blocks.fill(&mut callback, &mut process, |x| x);
+        data.extend(callback);
+    }
+
+    let expected: Vec<f32> = (0..9).flat_map(|x| [x as f32, -(x as f32)]).collect();
+    assert_eq!(data, expected);
+}
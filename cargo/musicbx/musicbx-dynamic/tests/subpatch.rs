@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use musicbx_core::AudioBuffer;
+use musicbx_dynamic::{GraphController, GraphError, PatchModule};
+use musicbx_std::{util, StdModuleDefinition};
+use musicbx_types::patch::{Patch, SYNTHETIC_OUTPUT_UID};
+use musicbx_types::SignalConversion;
+
+use common::{cable, node};
+
+mod common;
+
+const BLOCK_SIZE: usize = 64;
+
+// 1 Гц -> высота тона -> снова частота на входе -> высота тона на выходе подпатча
+fn pitch_patch() -> Patch {
+    let mut constant = node(1, util::ConstSig::definition().uid);
+    constant
+        .parameters
+        .insert("value".to_string(), "1.0".to_string());
+    let to_pitch = util::FreqToPitch::definition().uid;
+
+    Patch {
+        nodes: vec![
+            node(0, SYNTHETIC_OUTPUT_UID),
+            constant,
+            node(2, to_pitch),
+            node(3, to_pitch),
+        ],
+        cables: vec![
+            cable(1, "output", 2, "input"),
+            cable(2, "output", 3, "input"),
+            cable(3, "output", 0, "input"),
+        ],
+    }
+}
+
+#[test]
+fn subpatches_convert_pitch_cables_to_frequency() {
+    let patches = BTreeMap::from([("Pitch".to_string(), pitch_patch())]);
+    let module = PatchModule::new(&StdModuleDefinition, patches).unwrap();
+
+    let (mut controller, mut processor) = GraphController::new(48000.0, 16);
+    controller
+        .add_node(0, SYNTHETIC_OUTPUT_UID, &module)
+        .unwrap();
+    controller.add_node(1, "Pitch", &module).unwrap();
+    controller.connect(&cable(1, "output", 0, "input")).unwrap();
+
+    let mut block = AudioBuffer::new(1, BLOCK_SIZE);
+    processor.process(BLOCK_SIZE, &mut block);
+
+    // без преобразования второй узел получил бы высоту тона как частоту
+    let expected = SignalConversion::FrequencyToPitch.apply(1.0);
+    for x in block.channel(0) {
+        assert!((x - expected).abs() < 1e-3, "{x} != {expected}");
+    }
+}
+
+#[test]
+fn broken_subpatches_are_reported_when_the_module_is_built() {
+    let mut patch = pitch_patch();
+    patch.nodes[1]
+        .parameters
+        .insert("volume".to_string(), "0.5".to_string());
+    let patches = BTreeMap::from([("Pitch".to_string(), patch)]);
+
+    assert!(matches!(
+        PatchModule::new(&StdModuleDefinition, patches),
+        Err(GraphError::UnknownParameterName(name, _)) if name == "volume"
+    ));
+}
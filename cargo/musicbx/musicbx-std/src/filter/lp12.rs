@@ -1,29 +1,25 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
+use musicbx_derive::NodeParameters;
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone)]
 pub struct LP12Filter {
     _phase: f32,
 }
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::filter::LP12Filter")]
 pub struct LP12FilterParameters<'a> {
+    #[input]
     pub input: DataRef<'a>,
+    #[parameter(kind = HzWide, default = 22050.0, max = 22050.0)]
     pub cutoff: DataRef<'a>,
+    #[parameter(kind = Number, default = 0.71, min = 0.1, max = 10.0)]
     pub q: DataRef<'a>,
+    #[output]
     pub out: DataMut<'a>,
 }
 
-impl<'a> Default for LP12FilterParameters<'a> {
-    fn default() -> Self {
-        Self {
-            input: 0.0.into(),
-            cutoff: 22050f32.into(),
-            q: 0.71.into(),
-            out: 0.0.into(),
-        }
-    }
-}
-
 impl<'a> Node<'a> for LP12Filter {
     type Parameters = LP12FilterParameters<'a>;
 
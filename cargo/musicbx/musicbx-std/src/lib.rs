@@ -13,17 +13,26 @@ pub mod util;
 pub struct StdModuleDefinition;
 
 impl ModuleDefinition for StdModuleDefinition {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        NODE_DEFINITIONS.definitions()
+    }
+
     fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
-        NODE_DEFINITIONS.iter().find(|x| x.uid == uid)
+        NODE_DEFINITIONS.info_for_uid(uid)
     }
 }
 
 static NODE_DEFINITIONS: &[NodeDefinition] = &[
+    filter::LP12Filter::definition(),
+    osc::Lfo::definition(),
     osc::SimpleSawOsc::definition(),
     osc::SinOsc::definition(),
     util::Add::definition(),
     util::Amp::definition(),
+    util::ConstSig::definition(),
+    util::FreqToPitch::definition(),
     util::HardClip::definition(),
     util::Mul::definition(),
+    util::PitchToFreq::definition(),
     util::UniformRandom::definition(),
 ];
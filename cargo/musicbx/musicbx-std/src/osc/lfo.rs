@@ -0,0 +1,62 @@
+use musicbx::Node;
+use musicbx_core::{DataMut, DataRef};
+use musicbx_derive::{FromSampleRate, NodeParameters};
+
+#[derive(Debug, Clone, FromSampleRate)]
+pub struct Lfo {
+    phase: f32,
+    #[from(sr)]
+    sr: f32,
+}
+
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::osc::Lfo")]
+pub struct LfoParameters<'a> {
+    #[parameter(kind = HzSlow, default = 1.0)]
+    pub rate: DataRef<'a>,
+    #[parameter(kind = Choice, choices = ["Sine", "Triangle", "Saw", "Square"], default = 0.0)]
+    pub waveform: DataRef<'a>,
+    #[parameter(kind = Toggle, default = 1.0)]
+    pub bipolar: DataRef<'a>,
+    #[output(signal = Control)]
+    pub output: DataMut<'a>,
+}
+
+impl<'a> Node<'a> for Lfo {
+    type Parameters = LfoParameters<'a>;
+
+    fn process<const N: usize>(&mut self, n: usize, parameters: Self::Parameters) {
+        let Self::Parameters {
+            rate,
+            waveform,
+            bipolar,
+            output: mut out,
+        } = parameters;
+
+        for i in 0..n {
+            self.phase += rate[i] / self.sr;
+            self.phase %= 1.0;
+
+            // все формы сначала строятся в диапазоне от -1 до 1
+            let phase = self.phase;
+            let value = match waveform[i].round() as u32 {
+                0 => (phase * 2.0 * std::f32::consts::PI).sin(),
+                1 => 1.0 - 4.0 * (phase - 0.5).abs(),
+                2 => phase * 2.0 - 1.0,
+                _ => {
+                    if phase < 0.5 {
+                        1.0
+                    } else {
+                        -1.0
+                    }
+                }
+            };
+
+            out[i] = if bipolar[i] >= 0.5 {
+                value
+            } else {
+                (value + 1.0) * 0.5
+            };
+        }
+    }
+}
@@ -1,5 +1,7 @@
+pub use lfo::{Lfo, LfoParameters};
 pub use simple_saw::{SimpleSawOsc, SimpleSawOscParameters};
 pub use sin::{SinOsc, SinOscParameters};
 
+mod lfo;
 mod simple_saw;
 mod sin;
@@ -1,7 +1,6 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_derive::FromSampleRate;
-use musicbx_types::{NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind};
+use musicbx_derive::{FromSampleRate, NodeParameters};
 
 #[derive(Debug, Clone, FromSampleRate)]
 pub struct SimpleSawOsc {
@@ -10,22 +9,17 @@ pub struct SimpleSawOsc {
     sr: f32,
 }
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::osc::SimpleSawOsc")]
 pub struct SimpleSawOscParameters<'a> {
+    #[parameter(kind = HzWide, default = 440.0)]
     pub freq: DataRef<'a>,
+    #[input(default = 1.0, signal = Control, min = 0.0, max = 8.0)]
     pub tune: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for SimpleSawOscParameters<'a> {
-    fn default() -> Self {
-        Self {
-            freq: 440.0.into(),
-            tune: 1.0.into(),
-            output: 0.0.into(),
-        }
-    }
-}
-
 impl<'a> Node<'a> for SimpleSawOsc {
     type Parameters = SimpleSawOscParameters<'a>;
 
@@ -43,25 +37,3 @@ impl<'a> Node<'a> for SimpleSawOsc {
         }
     }
 }
-
-impl SimpleSawOsc {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::osc::SimpleSawOsc",
-            inputs: &[NodeInput {
-                number: 0,
-                name: "tune",
-            }],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[NodeParameter {
-                number: 0,
-                kind: NodeParameterKind::HzSlow,
-                default: "0.5",
-                name: "freq",
-            }],
-        }
-    }
-}
@@ -1,7 +1,6 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_derive::FromSampleRate;
-use musicbx_types::{NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind};
+use musicbx_derive::{FromSampleRate, NodeParameters};
 
 #[derive(Debug, Clone, FromSampleRate)]
 pub struct SinOsc {
@@ -10,24 +9,19 @@ pub struct SinOsc {
     sr: f32,
 }
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::osc::SinOsc")]
 pub struct SinOscParameters<'a> {
+    #[parameter(kind = HzWide, default = 440.0)]
     pub freq: DataRef<'a>,
-    pub tune: DataRef<'a>,
+    #[input]
     pub phase_mod: DataRef<'a>,
+    #[input(default = 1.0, signal = Control, min = 0.0, max = 8.0)]
+    pub tune: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for SinOscParameters<'a> {
-    fn default() -> Self {
-        Self {
-            freq: 440.0.into(),
-            tune: 1.0.into(),
-            phase_mod: 0.0.into(),
-            output: 0.0.into(),
-        }
-    }
-}
-
 impl<'a> Node<'a> for SinOsc {
     type Parameters = SinOscParameters<'a>;
 
@@ -46,31 +40,3 @@ impl<'a> Node<'a> for SinOsc {
         }
     }
 }
-
-impl SinOsc {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::osc::SinOsc",
-            inputs: &[
-                NodeInput {
-                    number: 0,
-                    name: "phase_mod",
-                },
-                NodeInput {
-                    number: 1,
-                    name: "tune",
-                },
-            ],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[NodeParameter {
-                number: 0,
-                kind: NodeParameterKind::HzWide,
-                default: "440.0",
-                name: "freq",
-            }],
-        }
-    }
-}
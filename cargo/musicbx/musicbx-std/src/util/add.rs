@@ -1,14 +1,18 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_types::{NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind};
+use musicbx_derive::NodeParameters;
 
 #[derive(Default, Debug, Copy, Clone)]
 pub struct Add;
 
-#[derive(Default)]
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::Add")]
 pub struct AddParameters<'a> {
+    #[input]
     pub a: DataRef<'a>,
+    #[parameter(kind = Number, default = 0.0)]
     pub b: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
@@ -23,31 +27,3 @@ impl<'a> Node<'a> for Add {
         }
     }
 }
-
-impl Add {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::util::Add",
-            inputs: &[
-                NodeInput {
-                    number: 0,
-                    name: "a",
-                },
-                NodeInput {
-                    number: 1,
-                    name: "b",
-                },
-            ],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[NodeParameter {
-                number: 0,
-                kind: NodeParameterKind::Number,
-                default: "0.0",
-                name: "b",
-            }],
-        }
-    }
-}
@@ -1,28 +1,22 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_types::{
-    to_amp, NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind,
-};
+use musicbx_derive::NodeParameters;
+use musicbx_types::to_amp;
 
 #[derive(Default, Debug, Clone)]
 pub struct Amp;
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::Amp")]
 pub struct AmpParameters<'a> {
+    #[input]
     pub input: DataRef<'a>,
+    #[parameter(kind = Db, default = -6.0)]
     pub db: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for AmpParameters<'a> {
-    fn default() -> Self {
-        Self {
-            input: DataRef::Float(0.0),
-            db: DataRef::Float(-6.0),
-            output: DataMut::Float(0.0),
-        }
-    }
-}
-
 impl<'a> Node<'a> for Amp {
     type Parameters = AmpParameters<'a>;
 
@@ -38,25 +32,3 @@ impl<'a> Node<'a> for Amp {
         }
     }
 }
-
-impl Amp {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::util::Amp",
-            inputs: &[NodeInput {
-                number: 0,
-                name: "input",
-            }],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[NodeParameter {
-                number: 0,
-                kind: NodeParameterKind::Db,
-                default: "-6.0",
-                name: "db",
-            }],
-        }
-    }
-}
@@ -1,20 +1,16 @@
 use musicbx::Node;
-use musicbx_core::DataMut;
+use musicbx_core::{DataMut, DataRef};
+use musicbx_derive::NodeParameters;
 
-#[derive(Default)]
-pub struct ConstSig {
-    value: f32,
-}
-
-impl ConstSig {
-    #[inline]
-    pub fn new(value: f32) -> Self {
-        Self { value }
-    }
-}
+#[derive(Default, Debug, Clone)]
+pub struct ConstSig;
 
-#[derive(Default)]
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::ConstSig")]
 pub struct ConstSigParameters<'a> {
+    #[parameter(kind = Number, default = 0.0)]
+    pub value: DataRef<'a>,
+    #[output(signal = Control)]
     pub output: DataMut<'a>,
 }
 
@@ -22,11 +18,10 @@ impl<'a> Node<'a> for ConstSig {
     type Parameters = ConstSigParameters<'a>;
 
     fn process<const N: usize>(&mut self, n: usize, parameters: ConstSigParameters) {
-        let ConstSigParameters { output } = parameters;
+        let ConstSigParameters { value, mut output } = parameters;
 
-        match output {
-            DataMut::Audio(audio) => audio[..n].fill(self.value),
-            DataMut::Float(_) => (),
-        };
+        for i in 0..n {
+            output[i] = value[i];
+        }
     }
 }
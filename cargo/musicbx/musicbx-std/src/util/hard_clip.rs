@@ -1,25 +1,19 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_types::{NodeDefinition, NodeInput, NodeOutput};
+use musicbx_derive::NodeParameters;
 
 #[derive(Default, Debug, Clone)]
 pub struct HardClip;
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::HardClip")]
 pub struct HardClipParameters<'a> {
+    #[input]
     pub input: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for HardClipParameters<'a> {
-    #[inline]
-    fn default() -> Self {
-        Self {
-            input: DataRef::Float(0.0),
-            output: DataMut::Float(0.0),
-        }
-    }
-}
-
 impl<'a> Node<'a> for HardClip {
     type Parameters = HardClipParameters<'a>;
 
@@ -37,20 +31,3 @@ impl<'a> Node<'a> for HardClip {
         }
     }
 }
-
-impl HardClip {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::util::HardClip",
-            inputs: &[NodeInput {
-                number: 0,
-                name: "input",
-            }],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[],
-        }
-    }
-}
@@ -4,6 +4,7 @@ pub use constsig::{ConstSig, ConstSigParameters};
 pub use hard_clip::{HardClip, HardClipParameters};
 pub use mul::{Mul, MulParameters};
 pub use noise::{UniformRandom, UniformRandomParameters};
+pub use pitch::{FreqToPitch, FreqToPitchParameters, PitchToFreq, PitchToFreqParameters};
 
 mod add;
 mod amp;
@@ -11,3 +12,4 @@ mod constsig;
 mod hard_clip;
 mod mul;
 mod noise;
+mod pitch;
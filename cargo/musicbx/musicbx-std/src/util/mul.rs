@@ -1,27 +1,21 @@
 use musicbx::Node;
 use musicbx_core::{DataMut, DataRef};
-use musicbx_types::{NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind};
+use musicbx_derive::NodeParameters;
 
 #[derive(Default, Debug, Clone)]
 pub struct Mul;
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::Mul")]
 pub struct MulParameters<'a> {
+    #[input(default = 1.0)]
     pub a: DataRef<'a>,
+    #[parameter(kind = Number, default = 1.0)]
     pub b: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for MulParameters<'a> {
-    #[inline]
-    fn default() -> Self {
-        Self {
-            a: DataRef::Float(1.0),
-            b: DataRef::Float(1.0),
-            output: DataMut::Float(0.0),
-        }
-    }
-}
-
 impl<'a> Node<'a> for Mul {
     type Parameters = MulParameters<'a>;
 
@@ -33,31 +27,3 @@ impl<'a> Node<'a> for Mul {
         }
     }
 }
-
-impl Mul {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::util::Mul",
-            inputs: &[
-                NodeInput {
-                    number: 0,
-                    name: "a",
-                },
-                NodeInput {
-                    number: 1,
-                    name: "b",
-                },
-            ],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[NodeParameter {
-                number: 0,
-                kind: NodeParameterKind::Number,
-                default: "1.0",
-                name: "b",
-            }],
-        }
-    }
-}
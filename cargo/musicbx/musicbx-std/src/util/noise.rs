@@ -2,7 +2,7 @@ use rand::prelude::*;
 
 use musicbx::{DataRef, Node};
 use musicbx_core::DataMut;
-use musicbx_types::{NodeDefinition, NodeOutput};
+use musicbx_derive::NodeParameters;
 
 #[derive(Debug, Clone)]
 pub struct UniformRandom {
@@ -17,22 +17,17 @@ impl Default for UniformRandom {
     }
 }
 
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::UniformRandom")]
 pub struct UniformRandomParameters<'a> {
+    #[input(default = -1.0, signal = Control)]
     pub low: DataRef<'a>,
+    #[input(default = 1.0, signal = Control)]
     pub high: DataRef<'a>,
+    #[output]
     pub output: DataMut<'a>,
 }
 
-impl<'a> Default for UniformRandomParameters<'a> {
-    fn default() -> Self {
-        Self {
-            low: DataRef::Float(-1.0),
-            high: DataRef::Float(1.0),
-            output: DataMut::Float(0.0),
-        }
-    }
-}
-
 impl<'a> Node<'a> for UniformRandom {
     type Parameters = UniformRandomParameters<'a>;
     fn process<const N: usize>(&mut self, n: usize, parameters: Self::Parameters) {
@@ -48,17 +43,3 @@ impl<'a> Node<'a> for UniformRandom {
         }
     }
 }
-
-impl UniformRandom {
-    pub const fn definition() -> NodeDefinition {
-        NodeDefinition {
-            uid: "musicbx::std::util::UniformRandom",
-            inputs: &[],
-            outputs: &[NodeOutput {
-                number: 0,
-                name: "output",
-            }],
-            parameters: &[],
-        }
-    }
-}
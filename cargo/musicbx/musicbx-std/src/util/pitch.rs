@@ -0,0 +1,55 @@
+use musicbx::Node;
+use musicbx_core::{DataMut, DataRef};
+use musicbx_derive::NodeParameters;
+use musicbx_types::SignalConversion;
+
+// эти узлы подставляет кодогенерация на кабели между высотой тона и частотой,
+// а динамический граф применяет то же преобразование прямо на кабеле
+
+#[derive(Default, Debug, Clone)]
+pub struct PitchToFreq;
+
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::PitchToFreq")]
+pub struct PitchToFreqParameters<'a> {
+    #[input(signal = Pitch)]
+    pub input: DataRef<'a>,
+    #[output(signal = Frequency)]
+    pub output: DataMut<'a>,
+}
+
+impl<'a> Node<'a> for PitchToFreq {
+    type Parameters = PitchToFreqParameters<'a>;
+
+    fn process<const N: usize>(&mut self, n: usize, parameters: Self::Parameters) {
+        let Self::Parameters { input, mut output } = parameters;
+
+        for i in 0..n {
+            output[i] = SignalConversion::PitchToFrequency.apply(input[i]);
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct FreqToPitch;
+
+#[derive(NodeParameters)]
+#[node_parameters(uid = "musicbx::std::util::FreqToPitch")]
+pub struct FreqToPitchParameters<'a> {
+    #[input(signal = Frequency)]
+    pub input: DataRef<'a>,
+    #[output(signal = Pitch)]
+    pub output: DataMut<'a>,
+}
+
+impl<'a> Node<'a> for FreqToPitch {
+    type Parameters = FreqToPitchParameters<'a>;
+
+    fn process<const N: usize>(&mut self, n: usize, parameters: Self::Parameters) {
+        let Self::Parameters { input, mut output } = parameters;
+
+        for i in 0..n {
+            output[i] = SignalConversion::FrequencyToPitch.apply(input[i]);
+        }
+    }
+}
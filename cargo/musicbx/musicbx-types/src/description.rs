@@ -2,31 +2,76 @@ use std::ops::Deref;
 
 use serde::Serialize;
 
-use crate::parameter::NodeParameterKind;
+use crate::parameter::{NodeParameterKind, ParameterCurve};
+use crate::signal::SignalType;
 
+/// Набор узлов, которые можно использовать в патче.
+/// Модули объединяются срезом: при совпадении uid используется узел из первого модуля
 pub trait ModuleDefinition {
-    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition>;
+    fn definitions(&self) -> Vec<&NodeDefinition>;
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        self.definitions().into_iter().find(|x| x.uid == uid)
+    }
 }
 
-impl ModuleDefinition for &[NodeDefinition] {
+impl ModuleDefinition for [NodeDefinition] {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        self.iter().collect()
+    }
+
     fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
         self.iter().find(|x| x.uid == uid)
     }
 }
 
-impl<T: ModuleDefinition> ModuleDefinition for &[T] {
+impl ModuleDefinition for Vec<NodeDefinition> {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        self.as_slice().definitions()
+    }
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        self.as_slice().info_for_uid(uid)
+    }
+}
+
+impl<T: ModuleDefinition> ModuleDefinition for [T] {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        let mut definitions: Vec<&NodeDefinition> = vec![];
+        for definition in self.iter().flat_map(|x| x.definitions()) {
+            if definitions.iter().all(|x| x.uid != definition.uid) {
+                definitions.push(definition);
+            }
+        }
+        definitions
+    }
+
     fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
         self.iter().filter_map(|x| x.info_for_uid(uid)).next()
     }
 }
 
-impl ModuleDefinition for Box<dyn ModuleDefinition> {
+impl<T: ModuleDefinition + ?Sized> ModuleDefinition for &T {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        (**self).definitions()
+    }
+
+    fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
+        (**self).info_for_uid(uid)
+    }
+}
+
+impl<T: ModuleDefinition + ?Sized> ModuleDefinition for Box<T> {
+    fn definitions(&self) -> Vec<&NodeDefinition> {
+        self.deref().definitions()
+    }
+
     fn info_for_uid(&self, uid: &str) -> Option<&NodeDefinition> {
         self.deref().info_for_uid(uid)
     }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Default, Clone)]
+#[derive(PartialEq, Debug, Serialize, Default, Clone)]
 pub struct NodeDefinition {
     pub uid: &'static str,
     pub inputs: &'static [NodeInput],
@@ -34,22 +79,67 @@ pub struct NodeDefinition {
     pub parameters: &'static [NodeParameter],
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+impl NodeDefinition {
+    /// Тип и ожидаемый диапазон сигнала входа.
+    /// Параметры тоже являются входами: сигнал определяется видом параметра,
+    /// а диапазон - его границами
+    pub fn input_signal(&self, name: &str) -> Option<(SignalType, (f32, f32))> {
+        let input = self.inputs.iter().find(|x| x.name == name);
+        let parameter = self.parameters.iter().find(|x| x.name == name);
+        match (input, parameter) {
+            (Some(input), _) => Some((input.signal, (input.min, input.max))),
+            (None, Some(parameter)) => {
+                Some((parameter.kind.signal(), (parameter.min, parameter.max)))
+            }
+            (None, None) => None,
+        }
+    }
+
+    pub fn output_signal(&self, name: &str) -> Option<(SignalType, (f32, f32))> {
+        let output = self.outputs.iter().find(|x| x.name == name)?;
+        Some((output.signal, (output.min, output.max)))
+    }
+}
+
+#[derive(PartialEq, Debug, Serialize, Clone)]
 pub struct NodeInput {
     pub number: usize,
     pub name: &'static str,
+    pub signal: SignalType,
+    // ожидаемый диапазон значений
+    pub min: f32,
+    pub max: f32,
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+#[derive(PartialEq, Debug, Serialize, Clone)]
 pub struct NodeOutput {
     pub number: usize,
     pub name: &'static str,
+    pub signal: SignalType,
+    pub min: f32,
+    pub max: f32,
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Clone)]
+#[derive(PartialEq, Debug, Serialize, Clone)]
 pub struct NodeParameter {
     pub number: usize,
     pub kind: NodeParameterKind,
+    pub min: f32,
+    pub max: f32,
+    pub curve: ParameterCurve,
     pub default: &'static str,
     pub name: &'static str,
+    // названия значений для параметров вида Choice
+    pub choices: &'static [&'static str],
+}
+
+// в патчах хранятся нормированные значения, а узлы работают с денормированными
+impl NodeParameter {
+    pub fn normalize(&self, value: f32) -> f32 {
+        self.curve.normalize(self.min, self.max, value)
+    }
+
+    pub fn denormalize(&self, normalized: f32) -> f32 {
+        self.curve.denormalize(self.min, self.max, normalized)
+    }
 }
@@ -0,0 +1,210 @@
+use thiserror::Error;
+
+use crate::{NodeParameter, NodeParameterKind, SignalConversion};
+
+#[derive(Debug, Error, PartialEq, Eq, Clone)]
+pub enum ParseValueError {
+    #[error("Empty value")]
+    Empty,
+
+    #[error("Invalid number {0:?}")]
+    InvalidNumber(String),
+
+    #[error("Unknown unit {unit:?} for a {kind:?} parameter")]
+    UnknownUnit {
+        unit: String,
+        kind: NodeParameterKind,
+    },
+
+    #[error("Unknown choice {0:?}")]
+    UnknownChoice(String),
+
+    #[error("Invalid note name {0:?}")]
+    InvalidNote(String),
+}
+
+// в патчах хранятся нормированные значения, а пользователю показываются значения с единицами
+impl NodeParameter {
+    pub fn format(&self, normalized: f32) -> String {
+        self.kind
+            .format_value(self.denormalize(normalized), self.choices)
+    }
+
+    pub fn parse(&self, text: &str) -> Result<f32, ParseValueError> {
+        let value = self.kind.parse_value(text, self.choices)?;
+        Ok(self.normalize(value))
+    }
+}
+
+impl NodeParameterKind {
+    /// `440 Hz`, `1.2 kHz`, `-6.0 dB`, `250 ms`, `+7 st`, `C#4`
+    pub fn format_value(&self, value: f32, choices: &[&str]) -> String {
+        use NodeParameterKind::*;
+        match self {
+            Number => format!("{value:.3}"),
+            HzSlow | HzFast | HzWide if value.abs() >= 1000.0 => {
+                format!("{} kHz", significant(value / 1000.0))
+            }
+            HzSlow | HzFast | HzWide => format!("{} Hz", significant(value)),
+            Db => format!("{value:.1} dB"),
+            Choice => {
+                let index = value.round();
+                match choices.get(index as usize) {
+                    Some(choice) if index >= 0.0 => choice.to_string(),
+                    _ => format!("{index}"),
+                }
+            }
+            Toggle if value >= 0.5 => "on".to_string(),
+            Toggle => "off".to_string(),
+            Integer => format!("{}", value.round()),
+            Time if value.abs() >= 1000.0 => format!("{} s", significant(value / 1000.0)),
+            Time => format!("{} ms", significant(value)),
+            Semitones => {
+                let sign = if value > 0.0 { "+" } else { "" };
+                format!("{sign}{} st", trim_zeros(format!("{value:.2}")))
+            }
+            Pitch => format_note(value.round() as i32),
+            Percent => format!("{}%", significant(value)),
+        }
+    }
+
+    /// Разбирает значение с единицами измерения, например `1.2k`, `-6 dB`, `C#4` или `Saw`.
+    /// Число без единиц понимается в тех же единицах, что и `denormalize`
+    pub fn parse_value(&self, text: &str, choices: &[&str]) -> Result<f32, ParseValueError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(ParseValueError::Empty);
+        }
+
+        use NodeParameterKind::*;
+        match self {
+            Choice => {
+                if let Some(index) = choices.iter().position(|x| x.eq_ignore_ascii_case(text)) {
+                    return Ok(index as f32);
+                }
+            }
+            Toggle => match text.to_ascii_lowercase().as_str() {
+                "on" | "true" | "yes" => return Ok(1.0),
+                "off" | "false" | "no" => return Ok(0.0),
+                _ => {}
+            },
+            HzSlow | HzFast | HzWide if text.starts_with(|x: char| x.is_ascii_alphabetic()) => {
+                let pitch = parse_note(text)? as f32;
+                return Ok(SignalConversion::PitchToFrequency.apply(pitch));
+            }
+            Pitch if text.starts_with(|x: char| x.is_ascii_alphabetic()) => {
+                return Ok(parse_note(text)? as f32);
+            }
+            _ => {}
+        }
+
+        let (number, unit) = split_unit(text);
+        let number: f32 = number.parse().map_err(|_| match self {
+            Choice => ParseValueError::UnknownChoice(text.to_string()),
+            _ => ParseValueError::InvalidNumber(text.to_string()),
+        })?;
+
+        let unit = unit.to_ascii_lowercase();
+        let scale = match (self, unit.as_str()) {
+            (_, "") => 1.0,
+            (HzSlow | HzFast | HzWide, "hz") => 1.0,
+            (HzSlow | HzFast | HzWide, "k" | "khz") => 1000.0,
+            (Db, "db") => 1.0,
+            (Time, "ms") => 1.0,
+            (Time, "s") => 1000.0,
+            (Semitones, "st") => 1.0,
+            (Percent, "%") => 1.0,
+            _ => Err(ParseValueError::UnknownUnit { unit, kind: *self })?,
+        };
+
+        let value = number * scale;
+        Ok(match self {
+            Choice | Toggle | Integer | Pitch => value.round(),
+            _ => value,
+        })
+    }
+}
+
+// три значащие цифры без лишних нулей: 440, 1.2, 0.00138
+fn significant(value: f32) -> String {
+    let magnitude = if value == 0.0 {
+        0
+    } else {
+        value.abs().log10().floor() as i32
+    };
+    let decimals = (2 - magnitude).max(0) as usize;
+    trim_zeros(format!("{value:.decimals$}"))
+}
+
+fn trim_zeros(number: String) -> String {
+    if number.contains('.') {
+        number
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        number
+    }
+}
+
+// `-1.5e3 ms` делится на `-1.5e3` и `ms`
+fn split_unit(text: &str) -> (&str, &str) {
+    let bytes = text.as_bytes();
+    let mut end = 0;
+    while end < bytes.len() {
+        let is_number = match bytes[end] {
+            b'0'..=b'9' | b'.' => true,
+            b'+' | b'-' => end == 0 || matches!(bytes[end - 1], b'e' | b'E'),
+            // показатель степени, а не начало единиц измерения
+            b'e' | b'E' => {
+                end > 0
+                    && bytes[end - 1].is_ascii_digit()
+                    && matches!(bytes.get(end + 1), Some(b'0'..=b'9' | b'+' | b'-'))
+            }
+            _ => false,
+        };
+        if !is_number {
+            break;
+        }
+        end += 1;
+    }
+
+    (text[..end].trim(), text[end..].trim())
+}
+
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+// MIDI-нота 61 - `C#4`, 0 - `C-1`
+fn format_note(midi: i32) -> String {
+    let name = NOTE_NAMES[midi.rem_euclid(12) as usize];
+    format!("{name}{}", midi.div_euclid(12) - 1)
+}
+
+// `A4` — MIDI-нота 69, диез пишется как `#`, бемоль как `b`
+fn parse_note(text: &str) -> Result<i32, ParseValueError> {
+    let invalid = || ParseValueError::InvalidNote(text.to_string());
+
+    let mut chars = text.chars();
+    let step = match chars.next().map(|x| x.to_ascii_uppercase()) {
+        Some('C') => 0,
+        Some('D') => 2,
+        Some('E') => 4,
+        Some('F') => 5,
+        Some('G') => 7,
+        Some('A') => 9,
+        Some('B') => 11,
+        _ => Err(invalid())?,
+    };
+
+    let rest = chars.as_str();
+    let (accidental, octave) = match rest.chars().next() {
+        Some('#') => (1, &rest[1..]),
+        Some('b') => (-1, &rest[1..]),
+        _ => (0, rest),
+    };
+    let octave: i32 = octave.trim().parse().map_err(|_| invalid())?;
+
+    Ok((octave + 1) * 12 + step + accidental)
+}
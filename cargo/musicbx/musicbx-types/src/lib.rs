@@ -1,6 +1,14 @@
 pub use description::*;
+pub use format::*;
 pub use parameter::*;
+pub use signal::*;
+pub use subpatch::*;
+pub use validation::*;
 
 mod description;
+mod format;
 mod parameter;
 pub mod patch;
+mod signal;
+mod subpatch;
+mod validation;
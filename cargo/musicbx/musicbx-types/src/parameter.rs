@@ -7,51 +7,118 @@ pub enum NodeParameterKind {
     HzFast,
     HzWide,
     Db,
+    // индекс одного из вариантов `NodeParameter::choices`
+    Choice,
+    Toggle,
+    Integer,
+    // в миллисекундах
+    Time,
+    Semitones,
+    // MIDI-нота, A4 = 69
+    Pitch,
+    Percent,
 }
 
+// диапазон и кривая по умолчанию для параметров этого вида
 impl NodeParameterKind {
-    fn min(&self) -> f32 {
+    pub const fn min(&self) -> f32 {
         use NodeParameterKind::*;
         match self {
             Number => 0.0,
-            HzSlow => 0.001f32.log2(),
-            HzFast => 20f32.log2(),
-            HzWide => 0.001f32.log2(),
+            HzSlow => 0.001,
+            HzFast => 20.0,
+            HzWide => 0.001,
             Db => -120.0,
+            Choice | Toggle | Integer => 0.0,
+            Time => 1.0,
+            Semitones => -48.0,
+            Pitch => 0.0,
+            Percent => 0.0,
         }
     }
 
-    fn max(&self) -> f32 {
+    pub const fn max(&self) -> f32 {
         use NodeParameterKind::*;
         match self {
             Number => 1.0,
-            HzSlow => 200f32.log2(),
-            HzFast => 22000f32.log2(),
-            HzWide => 22000f32.log2(),
+            HzSlow => 200.0,
+            HzFast => 22000.0,
+            HzWide => 22000.0,
             Db => 12.0,
+            Choice | Toggle => 1.0,
+            Integer => 16.0,
+            Time => 10000.0,
+            Semitones => 48.0,
+            Pitch => 127.0,
+            Percent => 100.0,
         }
     }
 
-    pub fn normalize(&self, denormalized: f32) -> f32 {
-        let x = (denormalized - self.min()) / (self.max() - self.min());
-
+    // у дискретных видов по шагу на каждое целое значение из диапазона
+    pub const fn curve(&self, min: f32, max: f32) -> ParameterCurve {
         use NodeParameterKind::*;
         match self {
-            Number => x,
-            HzSlow | HzFast | HzWide => 2.0f32.powf(x),
-            Db => x,
+            Number | Db | Semitones | Percent => ParameterCurve::Linear,
+            HzSlow | HzFast | HzWide | Time => ParameterCurve::Exponential,
+            Choice | Toggle | Integer | Pitch => ParameterCurve::Stepped {
+                steps: (max - min) as u32,
+            },
         }
     }
+}
 
-    pub fn denormalize(&self, normalized: f32) -> f32 {
-        let x = normalized * (self.max() - self.min()) + self.min();
+/// Отображение значения параметра из `[min, max]` в нормированное `[0, 1]` и обратно
+#[derive(PartialEq, Debug, Serialize, Deserialize, Copy, Clone)]
+#[serde(tag = "type")]
+pub enum ParameterCurve {
+    Linear,
+    // равным отрезкам соответствуют равные отношения, как октавы у частот; min и max > 0
+    Exponential,
+    // нормированное значение равно доле диапазона в степени skew
+    Skewed { skew: f32 },
+    // steps равных интервалов между min и max
+    Stepped { steps: u32 },
+}
 
-        use NodeParameterKind::*;
-        match self {
-            Number => x,
-            HzSlow | HzFast | HzWide => x.exp2(),
-            Db => x,
-        }
+// вычисления идут в f64, чтобы преобразования в обе стороны были обратными друг к другу
+// с точностью до округления в f32
+impl ParameterCurve {
+    pub fn normalize(&self, min: f32, max: f32, value: f32) -> f32 {
+        let (min, max, value) = (min as f64, max as f64, value as f64);
+        let x = (value - min) / (max - min);
+
+        use ParameterCurve::*;
+        let normalized = match *self {
+            Linear => x,
+            Exponential => (value / min).ln() / (max / min).ln(),
+            Skewed { skew } => x.clamp(0.0, 1.0).powf(skew as f64),
+            Stepped { steps } => {
+                let steps = steps.max(1) as f64;
+                (x * steps).round() / steps
+            }
+        };
+
+        normalized as f32
+    }
+
+    pub fn denormalize(&self, min: f32, max: f32, normalized: f32) -> f32 {
+        let (min, max, normalized) = (min as f64, max as f64, normalized as f64);
+
+        use ParameterCurve::*;
+        let value = match *self {
+            Linear => min + normalized * (max - min),
+            Exponential => min * (max / min).powf(normalized),
+            Skewed { skew } => {
+                min + normalized.clamp(0.0, 1.0).powf(1.0 / skew as f64) * (max - min)
+            }
+            Stepped { steps } => {
+                let steps = steps.max(1) as f64;
+                // сначала умножение: для целых границ шаг тогда получается без погрешности
+                min + (normalized * steps).round() * (max - min) / steps
+            }
+        };
+
+        value as f32
     }
 }
 
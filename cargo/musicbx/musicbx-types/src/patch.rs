@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub const SYNTHETIC_INPUT_UID: &str = "_synthetic_input";
+pub const SYNTHETIC_OUTPUT_UID: &str = "_synthetic_output";
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Patch {
     pub nodes: Vec<Node>,
     pub cables: Vec<Cable>,
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub id: usize,
     pub uid: String,
@@ -16,9 +20,66 @@ pub struct Node {
     pub parameters: HashMap<String, String>,
     #[serde(default)]
     pub collapsed: bool,
+    // имя поля сгенерированной структуры вместо `v{id}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    // имя порта для синтетических входов и выходов патча
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    // параметры, которые сгенерированный узел принимает снаружи: имя параметра -> имя входа
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub exposed: HashMap<String, String>,
+}
+
+impl Patch {
+    /// Патч без узлов, сигнал которых не доходит до выходов патча
+    pub fn without_dead_nodes(&self) -> Patch {
+        let mut live: HashSet<usize> = self
+            .nodes
+            .iter()
+            .filter(|x| x.uid == SYNTHETIC_OUTPUT_UID)
+            .map(|x| x.id)
+            .collect();
+
+        let mut stack: Vec<usize> = live.iter().copied().collect();
+        while let Some(id) = stack.pop() {
+            for cable in self.cables.iter().filter(|x| x.to.node_id == id) {
+                if live.insert(cable.from.node_id) {
+                    stack.push(cable.from.node_id);
+                }
+            }
+        }
+
+        Patch {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|x| live.contains(&x.id))
+                .cloned()
+                .collect(),
+            cables: self
+                .cables
+                .iter()
+                .filter(|x| live.contains(&x.from.node_id) && live.contains(&x.to.node_id))
+                .cloned()
+                .collect(),
+        }
+    }
 }
 
-#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+impl Node {
+    /// Имя порта синтетического узла: заданное в патче или имя сокета.
+    /// Моно-вход выходного узла по умолчанию становится выходом `output`
+    pub fn port_name<'a>(&'a self, socket_name: &'a str) -> &'a str {
+        match (&self.port, socket_name) {
+            (Some(port), _) => port,
+            (None, "input") if self.uid == SYNTHETIC_OUTPUT_UID => "output",
+            (None, socket_name) => socket_name,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
 pub struct GridOffset {
     pub x: i32,
     pub y: i32,
@@ -35,3 +96,15 @@ pub struct CableEnd {
     pub node_id: usize,
     pub socket_name: String,
 }
+
+impl Display for Cable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.from, self.to)
+    }
+}
+
+impl Display for CableEnd {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.node_id, self.socket_name)
+    }
+}
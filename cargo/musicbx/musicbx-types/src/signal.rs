@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{NodeDefinition, NodeParameterKind};
+
+/// Что передаётся по кабелю. Высота тона измеряется в MIDI-нотах (A4 = 69), частота - в герцах
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum SignalType {
+    #[default]
+    Audio,
+    Control,
+    Pitch,
+    Gate,
+    Frequency,
+}
+
+/// Преобразование, которое применяется к сигналу на кабеле между сокетами разных типов
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SignalConversion {
+    Identity,
+    PitchToFrequency,
+    FrequencyToPitch,
+}
+
+impl SignalType {
+    pub const fn min(&self) -> f32 {
+        match self {
+            SignalType::Audio | SignalType::Control => -1.0,
+            SignalType::Pitch | SignalType::Gate | SignalType::Frequency => 0.0,
+        }
+    }
+
+    pub const fn max(&self) -> f32 {
+        match self {
+            SignalType::Audio | SignalType::Control | SignalType::Gate => 1.0,
+            SignalType::Pitch => 127.0,
+            SignalType::Frequency => 22000.0,
+        }
+    }
+
+    /// None, если сигнал нельзя подать на вход такого типа
+    pub fn conversion_to(&self, input: SignalType) -> Option<SignalConversion> {
+        use SignalType::*;
+        match (self, input) {
+            (from, to) if *from == to => Some(SignalConversion::Identity),
+            // аудио и управляющие сигналы отличаются только назначением,
+            // а гейт - управляющий сигнал из нулей и единиц
+            (Audio, Control) | (Control, Audio) | (Gate, Control) => {
+                Some(SignalConversion::Identity)
+            }
+            (Pitch, Frequency) => Some(SignalConversion::PitchToFrequency),
+            (Frequency, Pitch) => Some(SignalConversion::FrequencyToPitch),
+            _ => None,
+        }
+    }
+}
+
+impl SignalConversion {
+    #[inline]
+    pub fn apply(&self, x: f32) -> f32 {
+        match self {
+            SignalConversion::Identity => x,
+            SignalConversion::PitchToFrequency => 440.0 * ((x - 69.0) / 12.0).exp2(),
+            // у нулевой и отрицательной частоты нет высоты, берём самую низкую
+            SignalConversion::FrequencyToPitch => 69.0 + 12.0 * (x.max(1e-3) / 440.0).log2(),
+        }
+    }
+}
+
+/// Преобразование на кабеле от выхода `output` к входу `input`.
+/// None, если сокет не найден или сигналы несовместимы
+pub fn socket_conversion(
+    from: &NodeDefinition,
+    output: &str,
+    to: &NodeDefinition,
+    input: &str,
+) -> Option<SignalConversion> {
+    let (from, _) = from.output_signal(output)?;
+    let (to, _) = to.input_signal(input)?;
+    from.conversion_to(to)
+}
+
+impl NodeParameterKind {
+    /// Тип сигнала, который ожидает вход параметра, если к нему подключен кабель
+    pub const fn signal(&self) -> SignalType {
+        use NodeParameterKind::*;
+        match self {
+            HzSlow | HzFast | HzWide => SignalType::Frequency,
+            Toggle => SignalType::Gate,
+            Pitch => SignalType::Pitch,
+            Number | Db | Choice | Integer | Time | Semitones | Percent => SignalType::Control,
+        }
+    }
+}
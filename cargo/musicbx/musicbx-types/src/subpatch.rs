@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::sync::Mutex;
+
+use thiserror::Error;
+
+use crate::patch::{Cable, Node, Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+use crate::{ModuleDefinition, NodeDefinition, NodeInput, NodeOutput, NodeParameter, SignalType};
+
+#[derive(Debug, Error)]
+pub enum SubpatchError {
+    #[error("Recursive patch inclusion: {}", .0.join(" -> "))]
+    RecursiveInclusion(Vec<String>),
+
+    #[error("Unknown node id {0}")]
+    UnknownNodeId(usize),
+
+    #[error("Invalid node UID: {0}")]
+    InvalidNodeUid(String),
+
+    #[error("Unknown parameter name {0} for node with uid {1}")]
+    UnknownParameterName(String, String),
+
+    #[error("Invalid parameter value: {0}")]
+    InvalidParameterValue(String),
+}
+
+/// Порядок сборки патчей: каждый патч идёт после патчей, на которые ссылаются его узлы.
+/// Подпатч указывается в `uid` узла своим именем
+pub fn dependency_order(patches: &BTreeMap<String, Patch>) -> Result<Vec<&str>, SubpatchError> {
+    let mut order = vec![];
+    for name in patches.keys() {
+        visit(name, patches, &mut vec![], &mut order)?;
+    }
+    Ok(order)
+}
+
+fn visit<'a>(
+    name: &'a str,
+    patches: &'a BTreeMap<String, Patch>,
+    stack: &mut Vec<&'a str>,
+    order: &mut Vec<&'a str>,
+) -> Result<(), SubpatchError> {
+    if order.contains(&name) {
+        return Ok(());
+    }
+    if let Some(start) = stack.iter().position(|x| *x == name) {
+        let chain = stack[start..].iter().chain([&name]);
+        Err(SubpatchError::RecursiveInclusion(
+            chain.map(ToString::to_string).collect(),
+        ))?;
+    }
+
+    stack.push(name);
+    for node in &patches[name].nodes {
+        if let Some((dependency, _)) = patches.get_key_value(node.uid.as_str()) {
+            visit(dependency, patches, stack, order)?;
+        }
+    }
+    stack.pop();
+
+    order.push(name);
+    Ok(())
+}
+
+/// Описание узла, в который превращается патч: входы и выходы берутся из синтетических узлов,
+/// а параметры - из выставленных наружу параметров.
+/// Входы, которые никуда не доходят, не становятся портами, а параметр остаётся у узла,
+/// даже если сейчас ни на что не влияет: патчи, которые его задают, продолжают собираться
+pub fn patch_definition(
+    uid: &str,
+    patch: &Patch,
+    module: &dyn ModuleDefinition,
+) -> Result<NodeDefinition, SubpatchError> {
+    let nodes: HashMap<usize, &Node> = patch.nodes.iter().map(|x| (x.id, x)).collect();
+    let node = |id| {
+        nodes
+            .get(&id)
+            .copied()
+            .ok_or(SubpatchError::UnknownNodeId(id))
+    };
+
+    let live = patch.without_dead_nodes();
+    let mut inputs: Vec<&str> = vec![];
+    let mut outputs: Vec<&str> = vec![];
+    for Cable { from, to } in &live.cables {
+        let from_node = node(from.node_id)?;
+        if from_node.uid == SYNTHETIC_INPUT_UID {
+            let name = from_node.port_name(&from.socket_name);
+            if !inputs.contains(&name) {
+                inputs.push(name);
+            }
+        }
+
+        let to_node = node(to.node_id)?;
+        if to_node.uid == SYNTHETIC_OUTPUT_UID {
+            let name = to_node.port_name(&to.socket_name);
+            if !outputs.contains(&name) {
+                outputs.push(name);
+            }
+        }
+    }
+
+    let mut parameters: Vec<NodeParameter> = vec![];
+    for (node, parameter, name) in exposed_parameters(patch) {
+        if parameters.iter().any(|x| x.name == name) {
+            continue;
+        }
+
+        let definition = module
+            .info_for_uid(&node.uid)
+            .ok_or_else(|| SubpatchError::InvalidNodeUid(node.uid.clone()))?
+            .parameters
+            .iter()
+            .find(|x| x.name == parameter)
+            .ok_or_else(|| {
+                SubpatchError::UnknownParameterName(parameter.to_string(), node.uid.clone())
+            })?;
+        let default = match node.parameters.get(parameter) {
+            Some(value) => {
+                let value: f32 = value
+                    .parse()
+                    .map_err(|_| SubpatchError::InvalidParameterValue(value.clone()))?;
+                intern(&format!("{:?}", definition.denormalize(value)))
+            }
+            None => definition.default,
+        };
+
+        parameters.push(NodeParameter {
+            number: parameters.len(),
+            kind: definition.kind,
+            min: definition.min,
+            max: definition.max,
+            curve: definition.curve,
+            default,
+            name: intern(name),
+            choices: definition.choices,
+        });
+    }
+
+    // порты патча, как и входы и выходы #[node], передают звук
+    let signal = SignalType::Audio;
+    let inputs = inputs
+        .into_iter()
+        .enumerate()
+        .map(|(number, name)| NodeInput {
+            number,
+            name: intern(name),
+            signal,
+            min: signal.min(),
+            max: signal.max(),
+        })
+        .collect::<Vec<_>>();
+    let outputs = outputs
+        .into_iter()
+        .enumerate()
+        .map(|(number, name)| NodeOutput {
+            number,
+            name: intern(name),
+            signal,
+            min: signal.min(),
+            max: signal.max(),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(NodeDefinition {
+        uid: intern(uid),
+        inputs: INPUTS.intern(inputs),
+        outputs: OUTPUTS.intern(outputs),
+        parameters: PARAMETERS.intern(parameters),
+    })
+}
+
+/// Выставленные наружу параметры в порядке узлов патча: узел, имя параметра, имя входа.
+/// Параметр, к которому подключен кабель, наружу не выставляется
+pub fn exposed_parameters(patch: &Patch) -> Vec<(&Node, &str, &str)> {
+    let overridden: HashSet<(usize, &str)> = patch
+        .cables
+        .iter()
+        .map(|x| (x.to.node_id, x.to.socket_name.as_str()))
+        .collect();
+
+    let mut result = vec![];
+    for node in &patch.nodes {
+        let mut exposed: Vec<_> = node.exposed.iter().collect();
+        exposed.sort();
+
+        for (parameter, name) in exposed {
+            if !overridden.contains(&(node.id, parameter.as_str())) {
+                result.push((node, parameter.as_str(), name.as_str()));
+            }
+        }
+    }
+    result
+}
+
+// описания узлов живут до конца программы, поэтому строки и срезы подпатчей никогда не освобождаются.
+// чтобы пересборка модуля не занимала память заново, одинаковые значения выделяются один раз
+static STRINGS: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+static INPUTS: Interned<NodeInput> = Interned::new();
+static OUTPUTS: Interned<NodeOutput> = Interned::new();
+static PARAMETERS: Interned<NodeParameter> = Interned::new();
+
+fn intern(s: &str) -> &'static str {
+    let mut strings = STRINGS.lock().unwrap();
+    match strings.get(s) {
+        Some(s) => s,
+        None => {
+            let s = Box::leak(s.to_owned().into_boxed_str());
+            strings.insert(s);
+            s
+        }
+    }
+}
+
+struct Interned<T: 'static>(Mutex<Vec<&'static [T]>>);
+
+impl<T: PartialEq> Interned<T> {
+    const fn new() -> Self {
+        Interned(Mutex::new(Vec::new()))
+    }
+
+    fn intern(&self, items: Vec<T>) -> &'static [T] {
+        let mut slices = self.0.lock().unwrap();
+        match slices.iter().find(|x| **x == items.as_slice()) {
+            Some(x) => x,
+            None => {
+                let items = Box::leak(items.into_boxed_slice());
+                slices.push(items);
+                items
+            }
+        }
+    }
+}
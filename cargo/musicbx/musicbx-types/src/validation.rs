@@ -0,0 +1,308 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::patch::{Cable, CableEnd, Node, Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+use crate::{ModuleDefinition, NodeDefinition, SignalConversion, SignalType};
+
+/// Проблема в патче, из-за которой его нельзя собрать
+#[derive(PartialEq, Eq, Debug, Clone, Error)]
+pub enum Diagnostic {
+    #[error("Duplicate node id {0}")]
+    DuplicateNodeId(usize),
+
+    #[error("Node {node_id} has invalid UID {uid}")]
+    InvalidNodeUid { node_id: usize, uid: String },
+
+    #[error("Cable {cable} refers to unknown node {node_id}")]
+    UnknownNodeId { cable: Cable, node_id: usize },
+
+    #[error("Cable {cable} refers to unknown socket {socket_name} of node with uid {uid}")]
+    UnknownSocketName {
+        cable: Cable,
+        socket_name: String,
+        uid: String,
+    },
+
+    #[error("Cable {0} must go from an output to an input")]
+    InvalidCableDirection(Cable),
+
+    #[error("Nodes {} form a cycle", display_cycle(.0))]
+    Cycle(Vec<usize>),
+
+    #[error("Node {node_id} has unknown parameter {name} for uid {uid}")]
+    UnknownParameterName {
+        node_id: usize,
+        name: String,
+        uid: String,
+    },
+
+    #[error("Node {node_id} has invalid value {value:?} for parameter {name}")]
+    InvalidParameterValue {
+        node_id: usize,
+        name: String,
+        value: String,
+    },
+}
+
+/// Кабель между сокетами с разными сигналами. Такой патч собирается,
+/// но, скорее всего, звучит не так, как задумано
+#[derive(PartialEq, Debug, Clone, Error)]
+pub enum SignalWarning {
+    #[error("Cable {cable} connects {from:?} output to {to:?} input")]
+    SignalMismatch {
+        cable: Cable,
+        from: SignalType,
+        to: SignalType,
+    },
+
+    #[error(
+        "Cable {cable} carries values in {}..{}, outside of the expected {}..{}",
+        .range.0, .range.1, .expected.0, .expected.1
+    )]
+    OutOfRange {
+        cable: Cable,
+        range: (f32, f32),
+        expected: (f32, f32),
+    },
+}
+
+/// Проверяет патч целиком и возвращает все найденные проблемы, а не только первую
+pub fn validate(patch: &Patch, module: &dyn ModuleDefinition) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+
+    // кабели ссылаются на первый узел с повторяющимся id
+    let mut nodes: HashMap<usize, &Node> = HashMap::new();
+    for node in &patch.nodes {
+        match nodes.entry(node.id) {
+            Entry::Occupied(_) => diagnostics.push(Diagnostic::DuplicateNodeId(node.id)),
+            Entry::Vacant(entry) => {
+                entry.insert(node);
+            }
+        }
+    }
+
+    let mut definitions: HashMap<usize, &NodeDefinition> = HashMap::new();
+    for node in &patch.nodes {
+        // у синтетических узлов может не быть описания, тогда их сокеты не проверяются
+        let definition = match module.info_for_uid(&node.uid) {
+            Some(definition) => definition,
+            None if is_synthetic(node) => continue,
+            None => {
+                diagnostics.push(Diagnostic::InvalidNodeUid {
+                    node_id: node.id,
+                    uid: node.uid.clone(),
+                });
+                continue;
+            }
+        };
+
+        validate_parameters(node, definition, &mut diagnostics);
+        definitions.entry(node.id).or_insert(definition);
+    }
+
+    let mut valid_cables = vec![];
+    for cable in &patch.cables {
+        let from = validate_cable_end(cable, &cable.from, &nodes, &definitions, Socket::Output);
+        let to = validate_cable_end(cable, &cable.to, &nodes, &definitions, Socket::Input);
+        match (from, to) {
+            (Ok(()), Ok(())) => valid_cables.push(cable),
+            (Err(err), _) | (_, Err(err)) => diagnostics.push(err),
+        }
+    }
+
+    diagnostics.extend(
+        find_cycles(patch, &valid_cables)
+            .into_iter()
+            .map(Diagnostic::Cycle),
+    );
+    diagnostics
+}
+
+/// Проверяет типы сигналов на кабелях, которые можно собрать.
+/// Остальные кабели отклоняет [`validate`], здесь они пропускаются
+pub fn signal_warnings(patch: &Patch, module: &dyn ModuleDefinition) -> Vec<SignalWarning> {
+    let definition = |id: usize| {
+        let node = patch.nodes.iter().find(|x| x.id == id)?;
+        module.info_for_uid(&node.uid)
+    };
+
+    patch
+        .cables
+        .iter()
+        .filter_map(|cable| {
+            let from = definition(cable.from.node_id)?;
+            let to = definition(cable.to.node_id)?;
+            check_cable_signal(cable, from, to)
+        })
+        .collect()
+}
+
+/// Преобразование между сигналами, например из высоты тона в частоту, предупреждением не считается.
+/// Диапазон выхода - только оценка (у Mul он зависит от множителя),
+/// поэтому предупреждение выдаётся, только если диапазоны вовсе не пересекаются
+pub fn check_cable_signal(
+    cable: &Cable,
+    from: &NodeDefinition,
+    to: &NodeDefinition,
+) -> Option<SignalWarning> {
+    let (from_signal, range) = from.output_signal(&cable.from.socket_name)?;
+    let (to_signal, expected) = to.input_signal(&cable.to.socket_name)?;
+
+    match from_signal.conversion_to(to_signal) {
+        None => Some(SignalWarning::SignalMismatch {
+            cable: cable.clone(),
+            from: from_signal,
+            to: to_signal,
+        }),
+        Some(SignalConversion::Identity) if range.1 < expected.0 || range.0 > expected.1 => {
+            Some(SignalWarning::OutOfRange {
+                cable: cable.clone(),
+                range,
+                expected,
+            })
+        }
+        Some(_) => None,
+    }
+}
+
+fn is_synthetic(node: &Node) -> bool {
+    node.uid == SYNTHETIC_INPUT_UID || node.uid == SYNTHETIC_OUTPUT_UID
+}
+
+fn validate_parameters(
+    node: &Node,
+    definition: &NodeDefinition,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let unknown_parameter = |name: &str| Diagnostic::UnknownParameterName {
+        node_id: node.id,
+        name: name.to_string(),
+        uid: node.uid.clone(),
+    };
+
+    let mut parameters: Vec<_> = node.parameters.iter().collect();
+    parameters.sort();
+    for (name, value) in parameters {
+        if !definition.parameters.iter().any(|x| x.name == name) {
+            diagnostics.push(unknown_parameter(name));
+        } else if value.parse::<f32>().is_err() {
+            diagnostics.push(Diagnostic::InvalidParameterValue {
+                node_id: node.id,
+                name: name.clone(),
+                value: value.clone(),
+            });
+        }
+    }
+
+    let mut exposed: Vec<_> = node.exposed.keys().collect();
+    exposed.sort();
+    for name in exposed {
+        if !definition.parameters.iter().any(|x| x.name == name) {
+            diagnostics.push(unknown_parameter(name));
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Socket {
+    Input,
+    Output,
+}
+
+fn validate_cable_end(
+    cable: &Cable,
+    end: &CableEnd,
+    nodes: &HashMap<usize, &Node>,
+    definitions: &HashMap<usize, &NodeDefinition>,
+    expected: Socket,
+) -> Result<(), Diagnostic> {
+    let node = nodes.get(&end.node_id).ok_or(Diagnostic::UnknownNodeId {
+        cable: cable.clone(),
+        node_id: end.node_id,
+    })?;
+
+    // у входа патча есть только выходы, а у выхода патча - только входы
+    let wrong_synthetic = match expected {
+        Socket::Output => node.uid == SYNTHETIC_OUTPUT_UID,
+        Socket::Input => node.uid == SYNTHETIC_INPUT_UID,
+    };
+    if wrong_synthetic {
+        Err(Diagnostic::InvalidCableDirection(cable.clone()))?;
+    }
+
+    let definition = match definitions.get(&end.node_id) {
+        Some(definition) => definition,
+        None => return Ok(()),
+    };
+
+    let name = end.socket_name.as_str();
+    let is_input = definition.inputs.iter().any(|x| x.name == name)
+        || definition.parameters.iter().any(|x| x.name == name);
+    let is_output = definition.outputs.iter().any(|x| x.name == name);
+
+    match (expected, is_input, is_output) {
+        (Socket::Input, true, _) | (Socket::Output, _, true) => Ok(()),
+        (_, true, _) | (_, _, true) => Err(Diagnostic::InvalidCableDirection(cable.clone())),
+        _ => Err(Diagnostic::UnknownSocketName {
+            cable: cable.clone(),
+            socket_name: name.to_string(),
+            uid: node.uid.clone(),
+        }),
+    }
+}
+
+// обход в глубину, каждое обратное ребро даёт один цикл
+fn find_cycles(patch: &Patch, cables: &[&Cable]) -> Vec<Vec<usize>> {
+    #[derive(Copy, Clone, PartialEq)]
+    enum State {
+        New,
+        InProgress,
+        Done,
+    }
+
+    fn visit(
+        id: usize,
+        cables: &[&Cable],
+        states: &mut HashMap<usize, State>,
+        stack: &mut Vec<usize>,
+        cycles: &mut Vec<Vec<usize>>,
+    ) {
+        states.insert(id, State::InProgress);
+        stack.push(id);
+
+        for cable in cables.iter().filter(|x| x.from.node_id == id) {
+            let next = cable.to.node_id;
+            match states.get(&next).copied().unwrap_or(State::New) {
+                State::New => visit(next, cables, states, stack, cycles),
+                State::InProgress => {
+                    let start = stack.iter().position(|x| *x == next).expect("on stack");
+                    cycles.push(stack[start..].to_vec());
+                }
+                State::Done => {}
+            }
+        }
+
+        stack.pop();
+        states.insert(id, State::Done);
+    }
+
+    let mut states = HashMap::new();
+    let mut cycles = vec![];
+    for node in &patch.nodes {
+        if !states.contains_key(&node.id) {
+            visit(node.id, cables, &mut states, &mut vec![], &mut cycles);
+        }
+    }
+    cycles
+}
+
+fn display_cycle(ids: &[usize]) -> String {
+    let ids: Vec<_> = ids
+        .iter()
+        .chain(ids.first())
+        .map(ToString::to_string)
+        .collect();
+    ids.join(" -> ")
+}
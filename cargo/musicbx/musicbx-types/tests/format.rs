@@ -0,0 +1,70 @@
+use musicbx_types::{NodeParameterKind, ParseValueError};
+
+use NodeParameterKind::*;
+
+const WAVEFORMS: &[&str] = &["Sine", "Triangle", "Saw", "Square"];
+
+#[test]
+fn values_are_formatted_with_units() {
+    assert_eq!(HzWide.format_value(440.0, &[]), "440 Hz");
+    assert_eq!(HzWide.format_value(1200.0, &[]), "1.2 kHz");
+    assert_eq!(HzSlow.format_value(0.25, &[]), "0.25 Hz");
+    assert_eq!(Db.format_value(-6.0, &[]), "-6.0 dB");
+    assert_eq!(Time.format_value(250.0, &[]), "250 ms");
+    assert_eq!(Time.format_value(1500.0, &[]), "1.5 s");
+    assert_eq!(Semitones.format_value(7.0, &[]), "+7 st");
+    assert_eq!(Pitch.format_value(61.0, &[]), "C#4");
+    assert_eq!(Percent.format_value(50.0, &[]), "50%");
+    assert_eq!(Choice.format_value(2.0, WAVEFORMS), "Saw");
+    assert_eq!(Toggle.format_value(1.0, &[]), "on");
+}
+
+#[test]
+fn values_are_parsed_with_units() {
+    assert_eq!(HzWide.parse_value("1.2 kHz", &[]), Ok(1200.0));
+    assert_eq!(HzWide.parse_value("1.2k", &[]), Ok(1200.0));
+    assert_eq!(HzWide.parse_value("440", &[]), Ok(440.0));
+    assert_eq!(Db.parse_value("-6 dB", &[]), Ok(-6.0));
+    assert_eq!(Time.parse_value("1.5s", &[]), Ok(1500.0));
+    assert_eq!(Percent.parse_value("50 %", &[]), Ok(50.0));
+    assert_eq!(Choice.parse_value("saw", WAVEFORMS), Ok(2.0));
+    assert_eq!(Toggle.parse_value("off", &[]), Ok(0.0));
+}
+
+#[test]
+fn note_names_are_parsed_as_frequencies() {
+    assert_eq!(HzWide.parse_value("A4", &[]), Ok(440.0));
+    let c_sharp = HzWide.parse_value("C#4", &[]).unwrap();
+    assert!((c_sharp - 277.18).abs() < 0.01, "{c_sharp}");
+    assert_eq!(
+        HzWide.parse_value("C#4", &[]),
+        HzWide.parse_value("Db4", &[])
+    );
+}
+
+#[test]
+fn pitches_round_trip_through_note_names() {
+    assert_eq!(Pitch.format_value(0.0, &[]), "C-1");
+    assert_eq!(Pitch.parse_value("Db4", &[]), Ok(61.0));
+    for midi in 0..=127 {
+        let text = Pitch.format_value(midi as f32, &[]);
+        assert_eq!(Pitch.parse_value(&text, &[]), Ok(midi as f32), "{text}");
+    }
+}
+
+#[test]
+fn invalid_values_are_rejected() {
+    assert_eq!(HzWide.parse_value("  ", &[]), Err(ParseValueError::Empty));
+    assert!(matches!(
+        Db.parse_value("-6 Hz", &[]),
+        Err(ParseValueError::UnknownUnit { .. })
+    ));
+    assert!(matches!(
+        Choice.parse_value("Noise", WAVEFORMS),
+        Err(ParseValueError::UnknownChoice(_))
+    ));
+    assert!(matches!(
+        HzWide.parse_value("H4", &[]),
+        Err(ParseValueError::InvalidNote(_))
+    ));
+}
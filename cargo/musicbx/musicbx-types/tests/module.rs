@@ -0,0 +1,33 @@
+use musicbx_types::{ModuleDefinition, NodeDefinition, NodeOutput, SignalType};
+
+const OUTPUT: &[NodeOutput] = &[NodeOutput {
+    number: 0,
+    name: "output",
+    signal: SignalType::Audio,
+    min: -1.0,
+    max: 1.0,
+}];
+
+fn definition(uid: &'static str, outputs: &'static [NodeOutput]) -> NodeDefinition {
+    NodeDefinition {
+        uid,
+        inputs: &[],
+        outputs,
+        parameters: &[],
+    }
+}
+
+#[test]
+fn composed_modules_list_every_uid_once() {
+    let first = vec![definition("a", OUTPUT), definition("shared", OUTPUT)];
+    let second = vec![definition("shared", &[]), definition("b", &[])];
+    let module = [first, second];
+
+    let uids: Vec<_> = module.definitions().iter().map(|x| x.uid).collect();
+    assert_eq!(uids, ["a", "shared", "b"]);
+
+    // при совпадении uid побеждает первый модуль
+    let shared = module.info_for_uid("shared").unwrap();
+    assert_eq!(shared.outputs.len(), 1);
+    assert_eq!(module.definitions()[1], shared);
+}
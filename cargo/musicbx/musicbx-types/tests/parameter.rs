@@ -0,0 +1,109 @@
+use proptest::prelude::*;
+
+use musicbx_types::{NodeParameterKind, ParameterCurve};
+
+const EPS: f32 = f32::EPSILON;
+
+// допуски ниже — оценка погрешности от округления промежуточного значения до f32
+fn assert_close(actual: f32, expected: f32, tolerance: f32) {
+    assert!(
+        (actual - expected).abs() <= tolerance,
+        "{actual} != {expected} (tolerance {tolerance})"
+    );
+}
+
+proptest! {
+    #[test]
+    fn linear_normalized_round_trips(min in -1000f32..1000.0, span in 0.01f32..1000.0, normalized in 0f32..=1.0) {
+        let curve = ParameterCurve::Linear;
+        let max = min + span;
+
+        let value = curve.denormalize(min, max, normalized);
+        // значение хранится с точностью до своего ulp, что в нормированных единицах делится на span
+        let tolerance = EPS * (1.0 + (min.abs() + span) / span);
+        assert_close(curve.normalize(min, max, value), normalized, tolerance);
+    }
+
+    #[test]
+    fn linear_value_round_trips(min in -1000f32..1000.0, span in 0.01f32..1000.0, t in -2f32..3.0) {
+        let curve = ParameterCurve::Linear;
+        let max = min + span;
+        // значения за пределами диапазона тоже встречаются в патчах
+        let value = min + t * span;
+
+        let normalized = curve.normalize(min, max, value);
+        let tolerance = EPS * (value.abs() + (value - min).abs());
+        assert_close(curve.denormalize(min, max, normalized), value, tolerance);
+    }
+
+    #[test]
+    fn exponential_round_trips(min in 0.001f32..100.0, ratio in 2f32..1e7, t in 0f32..=1.0) {
+        let curve = ParameterCurve::Exponential;
+        let max = min * ratio;
+
+        let value = curve.denormalize(min, max, t);
+        let normalized = curve.normalize(min, max, value);
+        assert_close(normalized, t, 2.0 * EPS);
+        // ошибка в нормированном значении растягивается в ln(ratio) раз
+        assert_close(curve.denormalize(min, max, normalized), value, value * (ratio.ln() + 2.0) * EPS);
+    }
+
+    #[test]
+    fn skewed_round_trips(span in 0.01f32..1000.0, skew in 0.25f32..4.0, normalized in 0f32..=1.0) {
+        let curve = ParameterCurve::Skewed { skew };
+
+        let value = curve.denormalize(0.0, span, normalized);
+        let tolerance = (skew + 2.0) * EPS * normalized;
+        assert_close(curve.normalize(0.0, span, value), normalized, tolerance);
+    }
+
+    #[test]
+    fn stepped_values_are_exact(min in -100f32..100.0, span in 1f32..100.0, steps in 1u32..64, step in 0u32..64) {
+        let curve = ParameterCurve::Stepped { steps };
+        let max = min + span;
+        let normalized = step.min(steps) as f32 / steps as f32;
+
+        let value = curve.denormalize(min, max, normalized);
+        prop_assert_eq!(curve.normalize(min, max, value), normalized);
+        prop_assert_eq!(curve.denormalize(min, max, curve.normalize(min, max, value)), value);
+    }
+
+    #[test]
+    fn stepped_values_snap_to_nearest_step(steps in 1u32..64, normalized in 0f32..=1.0) {
+        let curve = ParameterCurve::Stepped { steps };
+        let snapped = curve.normalize(0.0, 1.0, curve.denormalize(0.0, 1.0, normalized));
+
+        let step = snapped * steps as f32;
+        prop_assert!((step - step.round()).abs() < 1e-4);
+        prop_assert!((snapped - normalized).abs() <= 0.5 / steps as f32 + EPS);
+    }
+}
+
+proptest! {
+    #[test]
+    fn discrete_kinds_take_integer_values(min in -16i32..16, count in 1i32..32, normalized in 0f32..=1.0) {
+        let (min, max) = (min as f32, (min + count) as f32);
+
+        for kind in [NodeParameterKind::Choice, NodeParameterKind::Toggle, NodeParameterKind::Integer] {
+            let value = kind.curve(min, max).denormalize(min, max, normalized);
+            prop_assert_eq!(value, value.round());
+        }
+    }
+}
+
+#[test]
+fn kind_ranges_map_to_unit_interval() {
+    use NodeParameterKind::*;
+
+    let kinds = [
+        Number, HzSlow, HzFast, HzWide, Db, Choice, Toggle, Integer, Time, Semitones, Pitch,
+        Percent,
+    ];
+    for kind in kinds {
+        let (min, max) = (kind.min(), kind.max());
+        let curve = kind.curve(min, max);
+        assert_eq!(curve.normalize(min, max, min), 0.0);
+        assert_close(curve.normalize(min, max, max), 1.0, EPS);
+        assert_close(curve.denormalize(min, max, 1.0), max, max.abs() * EPS);
+    }
+}
@@ -0,0 +1,124 @@
+use proptest::prelude::*;
+
+use musicbx_types::patch::Patch;
+use musicbx_types::{
+    signal_warnings, NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind,
+    SignalConversion, SignalType, SignalWarning,
+};
+
+use common::{cable, node};
+
+mod common;
+
+proptest! {
+    #[test]
+    fn pitch_conversion_round_trips(pitch in 0f32..127.0) {
+        let frequency = SignalConversion::PitchToFrequency.apply(pitch);
+        let back = SignalConversion::FrequencyToPitch.apply(frequency);
+        prop_assert!((back - pitch).abs() < 1e-3, "{} != {}", back, pitch);
+    }
+}
+
+#[test]
+fn a4_is_440_hz() {
+    assert_eq!(SignalConversion::PitchToFrequency.apply(69.0), 440.0);
+    assert_eq!(SignalConversion::FrequencyToPitch.apply(440.0), 69.0);
+}
+
+fn socket_definition(uid: &'static str, signal: SignalType, min: f32, max: f32) -> NodeDefinition {
+    let inputs = vec![NodeInput {
+        number: 0,
+        name: "input",
+        signal,
+        min,
+        max,
+    }];
+    let outputs = vec![NodeOutput {
+        number: 0,
+        name: "output",
+        signal,
+        min,
+        max,
+    }];
+    NodeDefinition {
+        uid,
+        inputs: Box::leak(inputs.into_boxed_slice()),
+        outputs: Box::leak(outputs.into_boxed_slice()),
+        parameters: &[],
+    }
+}
+
+fn check(from: NodeDefinition, to: NodeDefinition) -> Vec<SignalWarning> {
+    let patch = Patch {
+        nodes: vec![node(0, from.uid), node(1, to.uid)],
+        cables: vec![cable(0, "output", 1, "input")],
+    };
+    signal_warnings(&patch, &vec![from, to])
+}
+
+#[test]
+fn mismatched_signals_are_reported() {
+    let audio = socket_definition("audio", SignalType::Audio, -1.0, 1.0);
+    let frequency = socket_definition("frequency", SignalType::Frequency, 0.0, 22000.0);
+    let warnings = check(audio, frequency);
+    assert!(matches!(
+        warnings[..],
+        [SignalWarning::SignalMismatch {
+            from: SignalType::Audio,
+            to: SignalType::Frequency,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn converted_and_compatible_signals_are_not_reported() {
+    let pitch = socket_definition("pitch", SignalType::Pitch, 0.0, 127.0);
+    let frequency = socket_definition("frequency", SignalType::Frequency, 0.0, 22000.0);
+    assert_eq!(check(pitch, frequency), vec![]);
+
+    let gate = socket_definition("gate", SignalType::Gate, 0.0, 1.0);
+    let control = socket_definition("control", SignalType::Control, -1.0, 1.0);
+    assert_eq!(check(gate, control), vec![]);
+}
+
+#[test]
+fn disjoint_ranges_are_reported() {
+    let low = socket_definition("low", SignalType::Control, -1.0, 0.0);
+    let high = socket_definition("high", SignalType::Control, 2.0, 8.0);
+    assert!(matches!(
+        check(low, high)[..],
+        [SignalWarning::OutOfRange { .. }]
+    ));
+}
+
+#[test]
+fn parameter_inputs_expect_the_parameter_range() {
+    let low = socket_definition("low", SignalType::Control, -1.0, 0.0);
+    let kind = NodeParameterKind::Number;
+    let parameter = vec![NodeParameter {
+        number: 0,
+        kind,
+        min: 2.0,
+        max: 8.0,
+        curve: kind.curve(2.0, 8.0),
+        default: "4.0",
+        name: "input",
+        choices: &[],
+    }];
+    let high = NodeDefinition {
+        uid: "high",
+        inputs: &[],
+        outputs: &[],
+        parameters: Box::leak(parameter.into_boxed_slice()),
+    };
+
+    // сигнал Control подходит, но до границ параметра не дотягивается
+    assert!(matches!(
+        check(low, high)[..],
+        [SignalWarning::OutOfRange {
+            expected: (2.0, 8.0),
+            ..
+        }]
+    ));
+}
@@ -0,0 +1,86 @@
+use std::ptr;
+
+use musicbx_types::patch::{Patch, SYNTHETIC_INPUT_UID, SYNTHETIC_OUTPUT_UID};
+use musicbx_types::{
+    patch_definition, NodeDefinition, NodeInput, NodeOutput, NodeParameter, NodeParameterKind,
+    SignalType,
+};
+
+use common::{cable, node};
+
+mod common;
+
+const AMP: NodeDefinition = NodeDefinition {
+    uid: "amp",
+    inputs: &[NodeInput {
+        number: 0,
+        name: "input",
+        signal: SignalType::Audio,
+        min: -1.0,
+        max: 1.0,
+    }],
+    outputs: &[NodeOutput {
+        number: 0,
+        name: "output",
+        signal: SignalType::Audio,
+        min: -1.0,
+        max: 1.0,
+    }],
+    parameters: &[NodeParameter {
+        number: 0,
+        kind: NodeParameterKind::Db,
+        min: NodeParameterKind::Db.min(),
+        max: NodeParameterKind::Db.max(),
+        curve: NodeParameterKind::Db.curve(-120.0, 12.0),
+        default: "-6.0",
+        name: "db",
+        choices: &[],
+    }],
+};
+
+// вход патча -> amp -> выход патча, громкость выставлена наружу как gain
+fn amp_patch() -> Patch {
+    let mut amp = node(1, AMP.uid);
+    amp.parameters.insert("db".to_string(), "0.5".to_string());
+    amp.exposed.insert("db".to_string(), "gain".to_string());
+
+    Patch {
+        nodes: vec![
+            node(0, SYNTHETIC_INPUT_UID),
+            amp,
+            node(2, SYNTHETIC_OUTPUT_UID),
+        ],
+        cables: vec![
+            cable(0, "input", 1, "input"),
+            cable(1, "output", 2, "input"),
+        ],
+    }
+}
+
+#[test]
+fn rebuilt_definitions_reuse_memory() {
+    let module = vec![AMP];
+    let first = patch_definition("Gain", &amp_patch(), &module).unwrap();
+    let second = patch_definition("Gain", &amp_patch(), &module).unwrap();
+
+    assert_eq!(first, second);
+    assert!(ptr::eq(first.uid, second.uid));
+    assert!(ptr::eq(first.inputs, second.inputs));
+    assert!(ptr::eq(first.outputs, second.outputs));
+    assert!(ptr::eq(first.parameters, second.parameters));
+    assert_eq!(first.parameters[0].name, "gain");
+}
+
+#[test]
+fn parameters_of_dead_nodes_stay_in_definition() {
+    // amp больше не доходит до выхода патча
+    let mut patch = amp_patch();
+    patch.cables.pop();
+    patch.cables.push(cable(0, "input", 2, "input"));
+
+    let definition = patch_definition("Gain", &patch, &vec![AMP]).unwrap();
+    assert_eq!(definition.inputs.len(), 1);
+    assert_eq!(definition.outputs.len(), 1);
+    let names: Vec<_> = definition.parameters.iter().map(|x| x.name).collect();
+    assert_eq!(names, ["gain"]);
+}
@@ -0,0 +1,149 @@
+use musicbx_std::{util, StdModuleDefinition};
+use musicbx_types::patch::{Patch, SYNTHETIC_OUTPUT_UID};
+use musicbx_types::{validate, Diagnostic};
+
+use common::{cable, node};
+
+mod common;
+
+const OUTPUT_ID: usize = 0;
+const AMP_ID: usize = 1;
+const ADD_ID: usize = 2;
+
+// add -> amp -> выход патча
+fn patch() -> Patch {
+    Patch {
+        nodes: vec![
+            node(OUTPUT_ID, SYNTHETIC_OUTPUT_UID),
+            node(AMP_ID, util::Amp::definition().uid),
+            node(ADD_ID, util::Add::definition().uid),
+        ],
+        cables: vec![
+            cable(ADD_ID, "output", AMP_ID, "input"),
+            cable(AMP_ID, "output", OUTPUT_ID, "input"),
+        ],
+    }
+}
+
+fn diagnostics(patch: &Patch) -> Vec<Diagnostic> {
+    validate(patch, &StdModuleDefinition)
+}
+
+#[test]
+fn valid_patch_has_no_diagnostics() {
+    let mut patch = patch();
+    patch.nodes[AMP_ID]
+        .parameters
+        .insert("db".to_string(), "0.5".to_string());
+    assert_eq!(diagnostics(&patch), vec![]);
+}
+
+#[test]
+fn unknown_uid_is_reported() {
+    let mut patch = patch();
+    patch.nodes.push(node(3, "musicbx::std::util::Nothing"));
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::InvalidNodeUid {
+            node_id: 3,
+            uid: "musicbx::std::util::Nothing".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn dangling_node_id_is_reported() {
+    let mut patch = patch();
+    let dangling = cable(7, "output", AMP_ID, "input");
+    patch.cables.push(dangling.clone());
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::UnknownNodeId {
+            cable: dangling,
+            node_id: 7,
+        }]
+    );
+}
+
+#[test]
+fn unknown_socket_is_reported() {
+    let mut patch = patch();
+    let unknown = cable(ADD_ID, "output", AMP_ID, "gain");
+    patch.cables.push(unknown.clone());
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::UnknownSocketName {
+            cable: unknown,
+            socket_name: "gain".to_string(),
+            uid: util::Amp::definition().uid.to_string(),
+        }]
+    );
+}
+
+#[test]
+fn input_to_input_cable_is_reported() {
+    let mut patch = patch();
+    let backwards = cable(AMP_ID, "input", ADD_ID, "a");
+    patch.cables.push(backwards.clone());
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::InvalidCableDirection(backwards)]
+    );
+}
+
+#[test]
+fn cycle_is_reported() {
+    let mut patch = patch();
+    patch.cables.push(cable(AMP_ID, "output", ADD_ID, "a"));
+    match &diagnostics(&patch)[..] {
+        [Diagnostic::Cycle(nodes)] => {
+            let mut nodes = nodes.clone();
+            nodes.sort_unstable();
+            nodes.dedup();
+            assert_eq!(nodes, vec![AMP_ID, ADD_ID]);
+        }
+        diagnostics => panic!("Expected a cycle, got {diagnostics:?}"),
+    }
+}
+
+#[test]
+fn unparsable_parameter_is_reported() {
+    let mut patch = patch();
+    patch.nodes[AMP_ID]
+        .parameters
+        .insert("db".to_string(), "loud".to_string());
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::InvalidParameterValue {
+            node_id: AMP_ID,
+            name: "db".to_string(),
+            value: "loud".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn unknown_parameter_is_reported() {
+    let mut patch = patch();
+    patch.nodes[AMP_ID]
+        .parameters
+        .insert("gain".to_string(), "0.5".to_string());
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::UnknownParameterName {
+            node_id: AMP_ID,
+            name: "gain".to_string(),
+            uid: util::Amp::definition().uid.to_string(),
+        }]
+    );
+}
+
+#[test]
+fn duplicate_id_is_reported() {
+    let mut patch = patch();
+    patch.nodes.push(node(ADD_ID, util::Mul::definition().uid));
+    assert_eq!(
+        diagnostics(&patch),
+        vec![Diagnostic::DuplicateNodeId(ADD_ID)]
+    );
+}
@@ -1,8 +1,15 @@
 #[cfg(feature = "codegen")]
 pub use musicbx_codegen as codegen;
-pub use musicbx_core::{DataMut, DataRef, FromSampleRate, Node};
+pub use musicbx_core::{
+    AudioBuffer, DataMut, DataRef, DynNode, DynParameters, Feedback, FromSampleRate, InputPort,
+    Node, OutputPort, Ports, MAX_BLOCK_SIZE,
+};
 pub use musicbx_derive::node;
+pub use musicbx_derive::DynParameters;
 pub use musicbx_derive::FromSampleRate;
+pub use musicbx_derive::NodeParameters;
+#[cfg(feature = "dynamic")]
+pub use musicbx_dynamic as dynamic;
 #[cfg(feature = "std")]
 pub use musicbx_std as std;
 pub use musicbx_types as types;
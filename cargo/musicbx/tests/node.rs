@@ -0,0 +1,315 @@
+use musicbx::std::{osc, util};
+use musicbx::types::{NodeParameterKind, ParameterCurve};
+use musicbx::{node, DataMut, DataRef, FromSampleRate, Node};
+
+// передаёт вход на выход и запоминает, какими кусками его вызывали
+#[derive(Default)]
+pub struct Probe {
+    sizes: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct ProbeParameters<'a> {
+    input: DataRef<'a>,
+    output: DataMut<'a>,
+}
+
+impl<'a> Node<'a> for Probe {
+    type Parameters = ProbeParameters<'a>;
+
+    fn process<const N: usize>(&mut self, n: usize, parameters: Self::Parameters) {
+        let ProbeParameters { input, mut output } = parameters;
+        self.sizes.push(n);
+
+        for i in 0..n {
+            output[i] = input[i];
+        }
+    }
+}
+
+#[node(
+    input -> first.a,
+    first.output -> delay(block) second.a,
+    second.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct BlockDelay {
+    first: util::Add,
+    second: util::Add,
+}
+
+#[node(
+    input -> first.a,
+    first.output -> delay(sample) second.a,
+    second.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct SampleDelay {
+    first: util::Add,
+    second: util::Add,
+}
+
+#[node(
+    input -> acc.a,
+    acc.output -> delay(block) acc.b,
+    acc.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct BlockAccumulator {
+    acc: util::Add,
+}
+
+#[node(
+    input -> acc.a,
+    acc.output -> delay(sample) acc.b,
+    acc.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct SampleAccumulator {
+    acc: util::Add,
+}
+
+#[node(
+    input -> probe.input,
+    probe.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct Probed {
+    probe: Probe,
+}
+
+#[node(
+    input -> probe.input,
+    probe.output -> output,
+    input -> first.a,
+    first.output -> delay(sample) second.a,
+    second.output -> delayed,
+)]
+#[derive(FromSampleRate)]
+pub struct ProbedSampleDelay {
+    probe: Probe,
+    first: util::Add,
+    second: util::Add,
+}
+
+#[node(
+    a -> first.a,
+    b -> first.a,
+    c -> second.a,
+    first.output -> output,
+    second.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct FanIn {
+    first: util::Add,
+    second: util::Add,
+}
+
+#[node(
+    a -> inner.a,
+    a -> inner.a,
+    inner.output -> output,
+    inner.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct RepeatedRoutes {
+    inner: util::Add,
+}
+
+// так кодогенерация объявляет выставленный наружу параметр с вариантами
+#[node(
+    shape: Choice(min = 0f32, max = 3f32, curve = Stepped { steps: 3 }, choices = ["Sine", "Triangle", "Saw", "Square"]) = 2f32,
+    resonance: Number(min = 0.1f32, max = 10f32, curve = Linear, choices = []) = 10f32,
+    shape -> lfo.waveform,
+    lfo.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct ExposedParameters {
+    lfo: osc::Lfo,
+}
+
+#[test]
+fn declared_parameters_keep_their_range_choices_and_default() {
+    let definition = ExposedParameters::definition();
+    let [shape, resonance] = definition.parameters else {
+        panic!("Unexpected parameters: {:?}", definition.parameters);
+    };
+
+    assert_eq!(shape.name, "shape");
+    assert_eq!(shape.kind, NodeParameterKind::Choice);
+    assert_eq!((shape.min, shape.max), (0.0, 3.0));
+    assert_eq!(shape.curve, ParameterCurve::Stepped { steps: 3 });
+    assert_eq!(shape.choices, ["Sine", "Triangle", "Saw", "Square"]);
+    assert_eq!(shape.default, "2.0");
+
+    assert_eq!(resonance.name, "resonance");
+    assert_eq!(resonance.kind, NodeParameterKind::Number);
+    assert_eq!((resonance.min, resonance.max), (0.1, 10.0));
+    assert_eq!(resonance.curve, ParameterCurve::Linear);
+    assert!(resonance.choices.is_empty());
+    assert_eq!(resonance.default, "10.0");
+}
+
+// так кодогенерация подаёт отрицательный постоянный параметр
+#[node(
+    input -> sum.a,
+    -0.25f32 -> sum.b,
+    sum.output -> output,
+)]
+#[derive(FromSampleRate)]
+pub struct NegativeConstant {
+    sum: util::Add,
+}
+
+#[test]
+fn negative_constants_are_routed_as_values() {
+    let mut node = NegativeConstant::from_sample_rate(48000.0);
+    assert!(NegativeConstant::definition()
+        .inputs
+        .iter()
+        .all(|x| x.name == "input"));
+
+    let mut output = [0.0; 4];
+    node.process::<4>(
+        4,
+        NegativeConstantParameters {
+            input: DataRef::from(&[1.0, 2.0, 3.0, 4.0]),
+            output: DataMut::from(&mut output),
+        },
+    );
+    assert_eq!(output, [0.75, 1.75, 2.75, 3.75]);
+}
+
+#[test]
+fn routes_into_the_same_socket_are_summed() {
+    let mut node = FanIn::from_sample_rate(48000.0);
+
+    let mut output = [0.0; 4];
+    node.process::<4>(
+        4,
+        FanInParameters {
+            a: DataRef::from(&[1.0, 2.0, 3.0, 4.0]),
+            b: DataRef::Float(10.0),
+            c: DataRef::from(&[100.0, 200.0, 300.0, 400.0]),
+            output: DataMut::from(&mut output),
+        },
+    );
+    assert_eq!(output, [111.0, 212.0, 313.0, 414.0]);
+}
+
+#[test]
+fn repeated_routes_are_not_doubled() {
+    let mut node = RepeatedRoutes::from_sample_rate(48000.0);
+
+    let mut output = [0.0; 4];
+    node.process::<4>(
+        4,
+        RepeatedRoutesParameters {
+            a: DataRef::from(&[1.0, 2.0, 3.0, 4.0]),
+            output: DataMut::from(&mut output),
+        },
+    );
+    assert_eq!(output, [1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn block_delay_lags_exactly_one_block() {
+    let mut node = BlockDelay::from_sample_rate(48000.0);
+
+    let mut output = [0.0; 4];
+    for (input, expected) in [
+        ([1.0, 2.0, 3.0, 4.0], [0.0, 0.0, 0.0, 0.0]),
+        ([5.0, 6.0, 7.0, 8.0], [1.0, 2.0, 3.0, 4.0]),
+        ([9.0, 10.0, 11.0, 12.0], [5.0, 6.0, 7.0, 8.0]),
+    ] {
+        node.process::<4>(
+            4,
+            BlockDelayParameters {
+                input: DataRef::from(&input),
+                output: DataMut::from(&mut output),
+            },
+        );
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn sample_delay_lags_one_sample_across_blocks() {
+    let mut node = SampleDelay::from_sample_rate(48000.0);
+
+    let mut output = [0.0; 4];
+    for (input, expected) in [
+        ([1.0, 2.0, 3.0, 4.0], [0.0, 1.0, 2.0, 3.0]),
+        ([5.0, 6.0, 7.0, 8.0], [4.0, 5.0, 6.0, 7.0]),
+    ] {
+        node.process::<4>(
+            4,
+            SampleDelayParameters {
+                input: DataRef::from(&input),
+                output: DataMut::from(&mut output),
+            },
+        );
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn delayed_cycles_are_accepted() {
+    let mut block = BlockAccumulator::from_sample_rate(48000.0);
+    let mut sample = SampleAccumulator::from_sample_rate(48000.0);
+
+    let input = [1.0; 4];
+    let mut output = [0.0; 4];
+    for expected in [[1.0; 4], [2.0; 4], [3.0; 4]] {
+        block.process::<4>(
+            4,
+            BlockAccumulatorParameters {
+                input: DataRef::from(&input),
+                output: DataMut::from(&mut output),
+            },
+        );
+        assert_eq!(output, expected);
+    }
+
+    for expected in [[1.0, 2.0, 3.0, 4.0], [5.0, 6.0, 7.0, 8.0]] {
+        sample.process::<4>(
+            4,
+            SampleAccumulatorParameters {
+                input: DataRef::from(&input),
+                output: DataMut::from(&mut output),
+            },
+        );
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn a_sample_delay_makes_every_inner_node_process_one_sample_at_a_time() {
+    let input = [1.0, 2.0, 3.0, 4.0];
+    let mut output = [0.0; 4];
+
+    let mut node = Probed::from_sample_rate(48000.0);
+    node.process::<4>(
+        4,
+        ProbedParameters {
+            input: DataRef::from(&input),
+            output: DataMut::from(&mut output),
+        },
+    );
+    assert_eq!(node.probe.sizes, [4]);
+    assert_eq!(output, input);
+
+    let mut delayed = [0.0; 4];
+    let mut node = ProbedSampleDelay::from_sample_rate(48000.0);
+    node.process::<4>(
+        4,
+        ProbedSampleDelayParameters {
+            input: DataRef::from(&input),
+            output: DataMut::from(&mut output),
+            delayed: DataMut::from(&mut delayed),
+        },
+    );
+    assert_eq!(node.probe.sizes, [1, 1, 1, 1]);
+    assert_eq!(output, input);
+    assert_eq!(delayed, [0.0, 1.0, 2.0, 3.0]);
+}
@@ -12,13 +12,15 @@ data class InputOutputSettings(
 data class DeviceSettings(
     val current: String?,
     val available: List<String>,
-    @SerialName("sample_rate") val sampleRate: SampleRateSettings?
+    @SerialName("sample_rate") val sampleRate: SampleRateSettings?,
+    val channels: ChannelsSettings? = null
 ) {
     companion object {
         val Unspecified = DeviceSettings(
             current = null,
             available = emptyList(),
-            sampleRate = null
+            sampleRate = null,
+            channels = null
         )
     }
 }
@@ -28,3 +30,9 @@ data class SampleRateSettings(
     val current: Double,
     val available: List<Double>
 )
+
+@Serializable
+data class ChannelsSettings(
+    val current: Int,
+    val available: List<Int>
+)
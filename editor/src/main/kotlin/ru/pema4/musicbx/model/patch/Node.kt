@@ -14,5 +14,8 @@ data class Node(
     val offset: GridOffset = GridOffset.Zero,
     @SerialName("parameters")
     val parameterValues: Map<String, String> = emptyMap(),
-    val collapsed: Boolean = false
+    val collapsed: Boolean = false,
+    val label: String? = null,
+    val port: String? = null,
+    val exposed: Map<String, String> = emptyMap()
 )
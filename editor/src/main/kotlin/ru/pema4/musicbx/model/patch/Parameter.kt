@@ -1,9 +1,9 @@
 package ru.pema4.musicbx.model.patch
 
 import androidx.compose.runtime.Immutable
+import kotlinx.serialization.SerialName
 import kotlinx.serialization.Serializable
-import kotlin.math.log2
-import kotlin.math.pow
+import ru.pema4.musicbx.service.ParameterFormatService
 
 @Immutable
 @Serializable
@@ -12,52 +12,60 @@ data class NodeParameter(
     val name: String,
     val description: String,
     val kind: NodeParameterKind,
-    val default: String
-)
-
-@Serializable
-enum class NodeParameterKind(
     val min: Float,
-    val max: Float
+    val max: Float,
+    val curve: ParameterCurve,
+    val default: String,
+    val choices: List<String> = emptyList()
 ) {
-    Number(min = 0.0f, max = 1.0f),
-    HzSlow(min = log2(0.001f), max = log2(200.0f)),
-    HzFast(min = log2(20.0f), max = log2(22000.0f)),
-    HzWide(min = log2(0.001f), max = log2(22000.0f)),
-    Db(min = -120.0f, max = 12.0f);
-
+    // Единицы измерения разбирает и форматирует бэкенд, чтобы редактор и musicbx-cli показывали одно и то же
     fun tryNormalize(displayValue: String): Float? {
-        val floatValue = displayValue.toFloatOrNull() ?: return null
-        val x = when (this) {
-            Number -> floatValue
-            HzSlow, HzFast, HzWide -> log2(floatValue)
-            Db -> floatValue
-        }
-
-        return normalizeRaw(x)
+        return ParameterFormatService.Native.parse(this, displayValue)
     }
 
     fun normalize(displayValue: String): Float {
         return tryNormalize(displayValue)
-            ?: error("Invalid display value $displayValue for parameter kind $this")
-    }
-
-    private fun normalizeRaw(raw: Float): Float {
-        return (raw - min) / (max - min)
+            ?: error("Invalid display value $displayValue for parameter $name")
     }
 
     fun display(normalized: Float): String {
-        val x = denormalizeRaw(normalized)
-
-        return when (this) {
-            Number -> "%.3f".format(x)
-            HzSlow, HzWide -> "%.3f".format(2.0f.pow(x))
-            HzFast -> "%.1f".format(2.0f.pow(x))
-            Db -> "%.3f".format(x)
-        }
+        return ParameterFormatService.Native.format(this, normalized)
     }
+}
 
-    private fun denormalizeRaw(raw: Float): Float {
-        return raw * (max - min) + min
-    }
+@Serializable
+enum class NodeParameterKind {
+    Number,
+    HzSlow,
+    HzFast,
+    HzWide,
+    Db,
+    Choice,
+    Toggle,
+    Integer,
+    Time,
+    Semitones,
+    Pitch,
+    Percent
+}
+
+// Повторяет ParameterCurve из musicbx-types, по ней слайдер выбирает шаг
+@Immutable
+@Serializable
+sealed class ParameterCurve {
+    @Serializable
+    @SerialName("Linear")
+    object Linear : ParameterCurve()
+
+    @Serializable
+    @SerialName("Exponential")
+    object Exponential : ParameterCurve()
+
+    @Serializable
+    @SerialName("Skewed")
+    data class Skewed(val skew: Float) : ParameterCurve()
+
+    @Serializable
+    @SerialName("Stepped")
+    data class Stepped(val steps: Int) : ParameterCurve()
 }
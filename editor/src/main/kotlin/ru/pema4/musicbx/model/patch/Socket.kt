@@ -8,6 +8,17 @@ sealed interface Socket {
     val number: Int
     val name: String
     val description: String
+    val signal: SignalType
+}
+
+// Повторяет SignalType из musicbx-types
+@Serializable
+enum class SignalType {
+    Audio,
+    Control,
+    Pitch,
+    Gate,
+    Frequency
 }
 
 @Immutable
@@ -15,12 +26,18 @@ sealed interface Socket {
 data class InputSocket(
     override val number: Int,
     override val name: String = "In $number",
-    override val description: String = "Input $number"
+    override val description: String = "Input $number",
+    override val signal: SignalType = SignalType.Audio,
+    val min: Float = -1.0f,
+    val max: Float = 1.0f
 ) : Socket
 
 @Serializable
 data class OutputSocket(
     override val number: Int,
     override val name: String = "Out $number",
-    override val description: String = "Output $number"
+    override val description: String = "Output $number",
+    override val signal: SignalType = SignalType.Audio,
+    val min: Float = -1.0f,
+    val max: Float = 1.0f
 ) : Socket
@@ -18,6 +18,7 @@ interface EditorService {
     fun connectNodes(from: CableFrom, to: CableTo)
     fun disconnectNodes(from: CableFrom, to: CableTo)
     fun setParameter(nodeId: Int, parameterNum: Int, normalizedValue: Float)
+    fun renderToWav(path: String, durationSeconds: Float)
 
     companion object {
         val Native: EditorService = NativeEditorService()
@@ -87,6 +88,8 @@ private class NativeEditorService(
     }
 
     external override fun setParameter(nodeId: Int, parameterNum: Int, normalizedValue: Float)
+
+    external override fun renderToWav(path: String, durationSeconds: Float)
 }
 
 private class NoOpEditorService(
@@ -101,4 +104,5 @@ private class NoOpEditorService(
     override fun connectNodes(from: CableFrom, to: CableTo) = Unit
     override fun disconnectNodes(from: CableFrom, to: CableTo) = Unit
     override fun setParameter(nodeId: Int, parameterNum: Int, normalizedValue: Float) = Unit
+    override fun renderToWav(path: String, durationSeconds: Float) = Unit
 }
@@ -0,0 +1,35 @@
+package ru.pema4.musicbx.service
+
+import kotlinx.serialization.encodeToString
+import kotlinx.serialization.json.Json
+import ru.pema4.musicbx.model.patch.NodeParameter
+
+interface ParameterFormatService {
+    fun format(parameter: NodeParameter, normalized: Float): String
+
+    fun parse(parameter: NodeParameter, text: String): Float?
+
+    companion object {
+        val Native: ParameterFormatService = NativeParameterFormatService
+        val Unspecified: ParameterFormatService = TestParameterFormatService
+    }
+}
+
+private object NativeParameterFormatService : ParameterFormatService {
+    private external fun formatParameter(parameter: String, normalized: Float): String
+    private external fun parseParameter(parameter: String, text: String): Float
+
+    override fun format(parameter: NodeParameter, normalized: Float): String {
+        return formatParameter(Json.encodeToString(parameter), normalized)
+    }
+
+    override fun parse(parameter: NodeParameter, text: String): Float? {
+        return parseParameter(Json.encodeToString(parameter), text).takeUnless { it.isNaN() }
+    }
+}
+
+private object TestParameterFormatService : ParameterFormatService {
+    override fun format(parameter: NodeParameter, normalized: Float): String = "%.3f".format(normalized)
+
+    override fun parse(parameter: NodeParameter, text: String): Float? = text.trim().toFloatOrNull()
+}
@@ -1,12 +1,15 @@
 package ru.pema4.musicbx.ui
 
 import androidx.compose.foundation.clickable
+import androidx.compose.foundation.layout.Box
 import androidx.compose.foundation.layout.Row
 import androidx.compose.foundation.layout.Spacer
 import androidx.compose.foundation.layout.width
 import androidx.compose.material.DropdownMenu
+import androidx.compose.material.DropdownMenuItem
 import androidx.compose.material.MaterialTheme
 import androidx.compose.material.Slider
+import androidx.compose.material.Switch
 import androidx.compose.material.Text
 import androidx.compose.material.TextField
 import androidx.compose.runtime.Composable
@@ -30,6 +33,7 @@ import androidx.compose.ui.text.input.TextFieldValue
 import androidx.compose.ui.unit.dp
 import ru.pema4.musicbx.model.patch.NodeParameter
 import ru.pema4.musicbx.model.patch.NodeParameterKind
+import ru.pema4.musicbx.model.patch.ParameterCurve
 import ru.pema4.musicbx.util.pointerHoverTip
 
 @Composable
@@ -63,12 +67,58 @@ fun ParameterView(
 
         Spacer(modifier.width(8.dp))
 
-        Slider(
-            value = current.normalized,
-            onValueChange = { current.normalized = it },
-            modifier = modifier
-                .pointerHoverTip("${state.parameter.description}: ${current.text}. Click on the name to set precise value")
+        val tipModifier = modifier
+            .pointerHoverTip("${state.parameter.description}: ${current.text}. Click on the name to set precise value")
+        when (state.parameter.kind) {
+            NodeParameterKind.Toggle -> Switch(
+                checked = current.normalized >= 0.5f,
+                onCheckedChange = { current.normalized = if (it) 1.0f else 0.0f },
+                modifier = tipModifier
+            )
+            NodeParameterKind.Choice -> ParameterChoice(
+                state = state,
+                modifier = tipModifier
+            )
+            else -> Slider(
+                value = current.normalized,
+                onValueChange = { current.normalized = it },
+                // у Slider steps — число промежуточных положений
+                steps = (state.parameter.curve as? ParameterCurve.Stepped)?.let { it.steps - 1 } ?: 0,
+                modifier = tipModifier
+            )
+        }
+    }
+}
+
+@Composable
+private fun ParameterChoice(
+    state: ParameterState,
+    modifier: Modifier = Modifier
+) {
+    var expanded by remember { mutableStateOf(false) }
+
+    Box(modifier) {
+        Text(
+            text = state.current.text,
+            style = MaterialTheme.typography.body1,
+            modifier = Modifier.clickable { expanded = true }
         )
+
+        DropdownMenu(
+            expanded = expanded,
+            onDismissRequest = { expanded = false }
+        ) {
+            for (choice in state.parameter.choices) {
+                DropdownMenuItem(
+                    onClick = {
+                        state.current.text = choice
+                        expanded = false
+                    }
+                ) {
+                    Text(choice)
+                }
+            }
+        }
     }
 }
 
@@ -92,7 +142,7 @@ private fun ParameterValueDropdown(
 
     val isError by remember {
         derivedStateOf {
-            state.parameter.kind.tryNormalize(textFieldValue.text) == null
+            state.parameter.tryNormalize(textFieldValue.text) == null
         }
     }
 
@@ -175,13 +225,13 @@ fun ParameterValue(
 
 fun ParameterValue(
     initial: String,
-    kind: NodeParameterKind,
+    parameter: NodeParameter,
     onChange: (Float) -> Unit = {}
 ): ParameterValue {
     return ParameterValue(
-        initialNormalized = kind.normalize(initial),
-        toDisplay = kind::display,
-        toNormalized = kind::normalize,
+        initialNormalized = parameter.normalize(initial),
+        toDisplay = parameter::display,
+        toNormalized = parameter::normalize,
         onChange = onChange
     )
 }
@@ -90,7 +90,7 @@ fun SocketView(
                 editor.createCable(socketEnd)
             }
             .hoverable(state.hoverInteractionSource)
-            .pointerHoverTip("${state.model.description}. Left Click to connect. Right Click to disconnect")
+            .pointerHoverTip("${state.model.description} (${state.model.signal}). Left Click to connect. Right Click to disconnect")
     ) {
         drawCircle(color = color)
         drawCircle(
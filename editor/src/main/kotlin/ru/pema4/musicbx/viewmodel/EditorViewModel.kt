@@ -134,7 +134,7 @@ class EditorViewModelImpl(
             editorService.setParameter(
                 nodeId = id,
                 parameterNum = parameter.number,
-                normalizedValue = parameter.kind.normalize(parameter.default)
+                normalizedValue = parameter.normalize(parameter.default)
             )
         }
 
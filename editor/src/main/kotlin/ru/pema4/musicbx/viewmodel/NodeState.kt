@@ -61,12 +61,12 @@ fun NodeViewModelImpl(
 
         val initialValue = node.parameterValues[it.name]
             ?.toFloat()
-            ?.let(it.kind::display) // normalized
+            ?.let(it::display) // normalized
             ?: it.default // not normalized
         ParameterState(
             model = it,
-            current = ParameterValue(initial = initialValue, kind = it.kind, onChange = onChange),
-            default = ParameterValue(initial = it.default, kind = it.kind)
+            current = ParameterValue(initial = initialValue, parameter = it, onChange = onChange),
+            default = ParameterValue(initial = it.default, parameter = it)
         )
     }
 